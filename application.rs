@@ -3,7 +3,7 @@ use glutin;
 
 use crate::ApplicationError;
 use core::traits::{RenderInfo, Rotatable, Translatable, Updatable};
-use core::{Camera, Config, Core, Float, Player};
+use core::{Camera, Config, Core, Float, Model, NodeId, Player, SceneGraph};
 use world::World;
 
 pub struct Application {
@@ -11,6 +11,9 @@ pub struct Application {
     camera: Camera,
     player: Player,
     world: World,
+    scene: SceneGraph,
+    player_node: NodeId,
+    camera_node: NodeId,
 	mouse_sensitivity: f32
 }
 
@@ -24,11 +27,21 @@ impl Application {
 
         camera.set_far(world.get_active_radius() * 8.);
 
+        // the camera follows the player at eye height
+        let mut scene = SceneGraph::default();
+        let player_node = scene.add_node(Model::default());
+        let mut camera_local = Model::default();
+        camera_local.set_translation(Vector3::new(0., 0., 3.));
+        let camera_node = scene.add_child(player_node, camera_local)?;
+
         let app = Application {
             core: core,
             camera: camera,
             player: player,
             world: world,
+            scene: scene,
+            player_node: player_node,
+            camera_node: camera_node,
 			mouse_sensitivity: config.get_float_or_default("mouse_sensitivity", 0.3)
         };
         Ok(app)
@@ -39,7 +52,7 @@ impl Application {
             self.core.update()?;
             if !self.core.is_hibernating() {
                 self.update_player()?;
-                self.update_camera();
+                self.update_camera()?;
                 self.update_world()?;
 
                 let mut render_info = RenderInfo::new(&self.camera);
@@ -64,12 +77,18 @@ impl Application {
         Ok(())
     }
 
-    fn update_camera(&mut self) {
-        self.player.align_camera(&mut self.camera);
+    fn update_camera(&mut self) -> Result<(), ApplicationError> {
+        self.scene
+            .get_local_mut(self.player_node)?
+            .set_translation(self.player.get_translation());
+        self.camera.set_rotation(self.player.get_rotation());
+        self.scene.apply(self.camera_node, &mut self.camera)?;
+        Ok(())
     }
 
     fn update_world(&mut self) -> Result<(), ApplicationError> {
         self.world.set_center(self.player.get_translation());
+        self.world.set_view_direction(self.player.get_direction());
         self.world.tick(self.core.get_time_passed())?;
         Ok(())
     }
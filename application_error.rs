@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::fmt;
 
-use core::{config::ConfigError, graphics::GraphicsError, CoreError, UpdateError};
+use core::{config::ConfigError, graphics::GraphicsError, scene::SceneError, CoreError, UpdateError};
 use world::WorldError;
 
 #[derive(Debug)]
@@ -11,6 +11,7 @@ pub enum ApplicationError {
     Config(ConfigError),
     World(WorldError),
     Graphics(GraphicsError),
+    Scene(SceneError),
 }
 
 impl From<CoreError> for ApplicationError {
@@ -43,6 +44,12 @@ impl From<GraphicsError> for ApplicationError {
     }
 }
 
+impl From<SceneError> for ApplicationError {
+    fn from(err: SceneError) -> Self {
+        ApplicationError::Scene(err)
+    }
+}
+
 impl Error for ApplicationError {
     fn description(&self) -> &str {
         match *self {
@@ -51,6 +58,7 @@ impl Error for ApplicationError {
             ApplicationError::Config(_) => "config",
             ApplicationError::World(_) => "world",
             ApplicationError::Graphics(_) => "graphics",
+            ApplicationError::Scene(_) => "scene",
         }
     }
 
@@ -61,6 +69,7 @@ impl Error for ApplicationError {
             ApplicationError::Config(ref err) => Some(err),
             ApplicationError::World(ref err) => Some(err),
             ApplicationError::Graphics(ref err) => Some(err),
+            ApplicationError::Scene(ref err) => Some(err),
         }
     }
 }
@@ -73,6 +82,7 @@ impl fmt::Display for ApplicationError {
             ApplicationError::Config(ref err) => write!(f, "{}/{}", self.description(), err),
             ApplicationError::World(ref err) => write!(f, "{}/{}", self.description(), err),
             ApplicationError::Graphics(ref err) => write!(f, "{}/{}", self.description(), err),
+            ApplicationError::Scene(ref err) => write!(f, "{}/{}", self.description(), err),
         }
     }
 }
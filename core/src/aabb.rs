@@ -0,0 +1,129 @@
+use std::fmt;
+
+use glm::{ Vector3, Matrix4, GenNum };
+
+use crate::Float;
+
+#[derive(Copy, Clone)]
+pub struct AABB {
+    min: Vector3<Float>,
+    max: Vector3<Float>
+}
+
+impl AABB {
+
+    pub fn new(min: Vector3<Float>, max: Vector3<Float>) -> AABB {
+        AABB {
+            min: min,
+            max: max
+        }
+    }
+
+    // returns the default box, if no points are given
+    pub fn from_points<I>(points: I) -> AABB
+    where I: IntoIterator<Item = Vector3<Float>> {
+        let mut iter = points.into_iter();
+        let first = match iter.next() {
+            Some(p) => p,
+            None => return AABB::default()
+        };
+        iter.fold(AABB::new(first, first), |mut aabb, p| {
+            for i in 0..3 {
+                aabb.min[i] = Float::min(aabb.min[i], p[i]);
+                aabb.max[i] = Float::max(aabb.max[i], p[i]);
+            }
+            aabb
+        })
+    }
+
+    pub fn get_min(&self) -> Vector3<Float> {
+        self.min
+    }
+
+    pub fn get_max(&self) -> Vector3<Float> {
+        self.max
+    }
+
+    pub fn get_center(&self) -> Vector3<Float> {
+        (self.min + self.max) / 2.
+    }
+
+    pub fn get_corners(&self) -> [Vector3<Float>; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            min,
+            Vector3::new(min.x, min.y, max.z),
+            Vector3::new(min.x, max.y, min.z),
+            Vector3::new(min.x, max.y, max.z),
+            Vector3::new(max.x, min.y, min.z),
+            Vector3::new(max.x, min.y, max.z),
+            Vector3::new(max.x, max.y, min.z),
+            max
+        ]
+    }
+
+    pub fn merge(&self, other: &AABB) -> AABB {
+        AABB::from_points(vec![self.min, self.max, other.min, other.max])
+    }
+
+    pub fn transform(&self, matrix: &Matrix4<Float>) -> AABB {
+        AABB::from_points(self.get_corners().iter().map(|p| (*matrix * p.extend(1.)).truncate(3)))
+    }
+
+    // returns the distance along the direction, at which the ray enters the box,
+    // or 0 if the origin is inside of the box
+    pub fn intersect_ray(&self, origin: Vector3<Float>, direction: Vector3<Float>) -> Option<Float> {
+        let mut t_near: Float = 0.;
+        let mut t_far = Float::INFINITY;
+        for i in 0..3 {
+            if direction[i] == 0. {
+                if origin[i] < self.min[i] || origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let t_a = (self.min[i] - origin[i]) / direction[i];
+            let t_b = (self.max[i] - origin[i]) / direction[i];
+            t_near = Float::max(t_near, Float::min(t_a, t_b));
+            t_far = Float::min(t_far, Float::max(t_a, t_b));
+            if t_near > t_far {
+                return None;
+            }
+        }
+        Some(t_near)
+    }
+
+    // returns the normal of the box face nearest to the point
+    pub fn get_face_normal(&self, point: Vector3<Float>) -> Vector3<Float> {
+        let mut normal = Vector3::from_s(0.);
+        let mut min_distance = Float::INFINITY;
+        for i in 0..3 {
+            for (bound, sign) in [(self.min[i], -1.), (self.max[i], 1.)].iter() {
+                let distance = (point[i] - bound).abs();
+                if distance < min_distance {
+                    min_distance = distance;
+                    normal = Vector3::from_s(0.);
+                    normal[i] = *sign;
+                }
+            }
+        }
+        normal
+    }
+}
+
+impl Default for AABB {
+    fn default() -> AABB {
+        AABB {
+            min: Vector3::from_s(0.),
+            max: Vector3::from_s(0.)
+        }
+    }
+}
+
+impl fmt::Display for AABB {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AABB: {}/{}/{} - {}/{}/{}",
+            self.min.x, self.min.y, self.min.z,
+            self.max.x, self.max.y, self.max.z)
+    }
+}
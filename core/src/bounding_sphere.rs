@@ -0,0 +1,86 @@
+use std::fmt;
+
+use glm::{ Vector3, Matrix4, GenNum, dot, length };
+
+use crate::{ Float, AABB };
+
+#[derive(Copy, Clone)]
+pub struct BoundingSphere {
+    center: Vector3<Float>,
+    radius: Float
+}
+
+impl BoundingSphere {
+
+    pub fn new(center: Vector3<Float>, radius: Float) -> BoundingSphere {
+        BoundingSphere {
+            center: center,
+            radius: radius
+        }
+    }
+
+    // centered on the bounds of the points, which is not minimal, but tight enough for culling
+    pub fn from_points<I>(points: I) -> BoundingSphere
+    where I: IntoIterator<Item = Vector3<Float>> + Clone {
+        let center = AABB::from_points(points.clone()).get_center();
+        let radius = points.into_iter()
+            .map(|p| length(p - center))
+            .fold(0., Float::max);
+        BoundingSphere::new(center, radius)
+    }
+
+    pub fn get_center(&self) -> Vector3<Float> {
+        self.center
+    }
+
+    pub fn get_radius(&self) -> Float {
+        self.radius
+    }
+
+    // the radius grows by the largest scale of the matrix, so the sphere stays conservative
+    pub fn transform(&self, matrix: &Matrix4<Float>) -> BoundingSphere {
+        let center = (*matrix * self.center.extend(1.)).truncate(3);
+        let scale = (0..3)
+            .map(|i| length(matrix[i].truncate(3)))
+            .fold(0., Float::max);
+        BoundingSphere::new(center, self.radius * scale)
+    }
+
+    // smallest sphere enclosing both spheres
+    pub fn merge(&self, other: &BoundingSphere) -> BoundingSphere {
+        let distance = length(other.center - self.center);
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+        let radius = (distance + self.radius + other.radius) / 2.;
+        let center = self.center + (other.center - self.center) * ((radius - self.radius) / distance);
+        BoundingSphere::new(center, radius)
+    }
+
+    // cheap test before the exact ones, the direction must be normalized
+    pub fn intersects_ray(&self, origin: Vector3<Float>, direction: Vector3<Float>, max_distance: Float) -> bool {
+        let to_center = self.center - origin;
+        let t = Float::min(Float::max(dot(to_center, direction), 0.), max_distance);
+        let closest = origin + direction * t;
+        length(self.center - closest) <= self.radius
+    }
+}
+
+impl Default for BoundingSphere {
+    fn default() -> BoundingSphere {
+        BoundingSphere {
+            center: Vector3::from_s(0.),
+            radius: 0.
+        }
+    }
+}
+
+impl fmt::Display for BoundingSphere {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BoundingSphere: {}/{}/{}, r = {}",
+            self.center.x, self.center.y, self.center.z, self.radius)
+    }
+}
@@ -1,14 +1,18 @@
 use std::ops::Add;
-use glm::{ Vector3, Matrix4 };
+use glm::{ Vector3, Matrix4, GenNum, normalize };
 use glm::ext::{ look_at, perspective };
 use num_traits::One;
 
 use crate::graphics::{ Model, Projection, create_direction, projection::create_default_perspective };
 use crate::Float;
 use crate::traits::{ Translatable, Rotatable };
+use crate::scene::SceneNode;
 
 pub struct Camera {
+    // the transform of the camera, its rotation follows the convention of the scene graph
     model: Model,
+    // the view direction as angles around the z axis and from it, relative to the model
+    view_rotation: Vector3<Float>,
     projection: Projection,
     view_matrix: Matrix4<Float>,
     projection_matrix: Matrix4<Float>,
@@ -32,6 +36,10 @@ impl Camera {
         self.projection_matrix * self.view_matrix * model.get_matrix()
     }
 
+    pub fn create_view_projection_matrix(&self) -> Matrix4<Float> {
+        self.projection_matrix * self.view_matrix
+    }
+
     pub fn set_projection(&mut self, new_projection: Projection) {
         self.projection = new_projection;
         self.update_projection();
@@ -41,15 +49,21 @@ impl Camera {
         self.projection
     }
 
+    // in world space, the view direction is rotated by the world matrix of the camera
     pub fn get_direction(&self) -> Vector3<Float> {
-        create_direction(self.model.get_rotation())
+        let direction = create_direction(self.view_rotation);
+        normalize((*self.model.get_matrix_ref() * direction.extend(0.)).truncate(3))
+    }
+
+    pub fn get_world_translation(&self) -> Vector3<Float> {
+        self.model.get_world_translation()
     }
 
     fn update_view(&mut self) {
-        let direction = create_direction(self.model.get_rotation());
+        let position = self.model.get_world_translation();
         self.view_matrix = look_at(
-            self.model.get_translation(),
-            self.model.get_translation().add(direction),
+            position,
+            position.add(self.get_direction()),
             Vector3::<Float>::new(0., 0., 1.));
     }
 
@@ -69,6 +83,7 @@ impl Default for Camera {
     fn default() -> Camera {
         let mut camera = Camera {
             model: Model::default(),
+            view_rotation: Vector3::from_s(0.),
             projection:  create_default_perspective(),
             view_matrix: Matrix4::<Float>::one(),
             projection_matrix: Matrix4::<Float>::one(),
@@ -79,6 +94,13 @@ impl Default for Camera {
     }
 }
 
+impl SceneNode for Camera {
+    fn set_scene_transform(&mut self, local: &Model, parent_matrix: Option<&Matrix4<Float>>) {
+        self.model.set_scene_transform(local, parent_matrix);
+        self.update_view();
+    }
+}
+
 impl Translatable for Camera {
     fn set_translation(&mut self, new_translation: Vector3<Float>) {
         self.model.set_translation(new_translation);
@@ -89,6 +111,7 @@ impl Translatable for Camera {
     }
 }
 
+// the rotation of the camera sets its view direction relative to its transform
 impl Rotatable for Camera {
     fn set_rotation(&mut self, new_rotation: Vector3<Float>) {
         const THRESHOLD: f32 = 0.01;
@@ -107,10 +130,10 @@ impl Rotatable for Camera {
             fixed_rotation.y = MAX_Y;
         }
 
-        self.model.set_rotation(fixed_rotation);
+        self.view_rotation = fixed_rotation;
         self.update_view();
     }
     fn get_rotation(&self) -> Vector3<Float> {
-        self.model.get_rotation()
+        self.view_rotation
     }
 }
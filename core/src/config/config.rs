@@ -81,4 +81,19 @@ impl Config {
             Err(_) => default
         }
     }
+
+    pub fn get_bool(&self, key: &str) -> Result<bool, ConfigError> {
+        match self.entry_map.get(key) {
+            Some(v) if v.is_bool() => Ok(v.as_bool().unwrap()),
+            Some(_other_val) => Err(ConfigError::InvalidValueType(key.to_owned(), "bool".to_owned())),
+            _ => Err(ConfigError::UnknownKey(key.to_owned()))
+        }
+    }
+
+    pub fn get_bool_or_default(&self, key: &str, default: bool) -> bool {
+        match self.get_bool(key) {
+            Ok(v) => v,
+            Err(_) => default
+        }
+    }
 }
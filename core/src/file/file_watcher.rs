@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::SystemTime;
+
+// notices changes of files by their modification times, which get compared on every poll
+pub struct FileWatcher {
+    files: BTreeMap<String, Option<SystemTime>>
+}
+
+impl FileWatcher {
+    // files already watched keep their last known modification time
+    pub fn watch(&mut self, path: &str) {
+        if !self.files.contains_key(path) {
+            trace!("Watching file '{}'", path);
+            self.files.insert(path.to_string(), get_modified_time(path));
+        }
+    }
+
+    pub fn get_file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    // files changed since the last poll, deleted files are reported once they exist again
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+        for (path, modified) in self.files.iter_mut() {
+            let current = get_modified_time(path);
+            if current != *modified {
+                *modified = current;
+                if current.is_some() {
+                    changed.push(path.clone());
+                }
+            }
+        }
+        changed
+    }
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self {
+            files: BTreeMap::new()
+        }
+    }
+}
+
+fn get_modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -1,6 +1,8 @@
 
 pub mod read_file;
 pub mod file_error;
+pub mod file_watcher;
 
 pub use self::read_file::read_file;
-pub use self::file_error::FileError;
\ No newline at end of file
+pub use self::file_error::FileError;
+pub use self::file_watcher::FileWatcher;
\ No newline at end of file
@@ -0,0 +1,53 @@
+use glm::{ Vector3, Vector4, Matrix4, dot, length };
+
+use crate::{ Float, AABB, BoundingSphere };
+
+// the six clipping planes of a view projection matrix in the space the matrix transforms from,
+// planes point inwards and are normalized, so they give distances
+pub struct Frustum {
+    planes: [Vector4<Float>; 6]
+}
+
+impl Frustum {
+
+    pub fn from_matrix(matrix: &Matrix4<Float>) -> Frustum {
+        let row = |i: usize| Vector4::new(matrix[0][i], matrix[1][i], matrix[2][i], matrix[3][i]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let mut planes = [w + x, w - x, w + y, w - y, w + z, w - z];
+        for plane in planes.iter_mut() {
+            let normal_length = length(plane.truncate(3));
+            if normal_length > 0. {
+                *plane = *plane / normal_length;
+            }
+        }
+        Frustum {
+            planes: planes
+        }
+    }
+
+    pub fn contains_point(&self, point: Vector3<Float>) -> bool {
+        self.planes.iter().all(|plane| get_plane_distance(plane, point) >= 0.)
+    }
+
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes.iter()
+            .all(|plane| get_plane_distance(plane, sphere.get_center()) >= -sphere.get_radius())
+    }
+
+    // conservative, boxes near the frustum corners may pass
+    pub fn intersects_aabb(&self, aabb: &AABB) -> bool {
+        let (min, max) = (aabb.get_min(), aabb.get_max());
+        self.planes.iter().all(|plane| {
+            // the corner furthest along the plane normal
+            let corner = Vector3::new(
+                if plane.x >= 0. { max.x } else { min.x },
+                if plane.y >= 0. { max.y } else { min.y },
+                if plane.z >= 0. { max.z } else { min.z });
+            get_plane_distance(plane, corner) >= 0.
+        })
+    }
+}
+
+fn get_plane_distance(plane: &Vector4<Float>, point: Vector3<Float>) -> Float {
+    dot(plane.truncate(3), point) + plane.w
+}
@@ -0,0 +1,115 @@
+use gl;
+use gl::types::{ GLint, GLuint, GLsizei };
+
+use crate::graphics::{ Texture, GraphicsError, OpenglError, check_opengl_error };
+
+// offscreen render target with a rgba color texture and a depth buffer,
+// the color texture can be kept after rendering, e.g. for baked textures
+pub struct Framebuffer {
+    fbo: GLuint,
+    depth_rbo: GLuint,
+    color_texture: Option<Texture>,
+    size: [GLsizei; 2]
+}
+
+impl Framebuffer {
+    pub fn new(size: [u32; 2]) -> Result<Framebuffer, GraphicsError> {
+        let size = [size[0] as GLsizei, size[1] as GLsizei];
+        let color_texture = create_color_texture(size)?;
+        let mut framebuffer = Framebuffer {
+            fbo: 0,
+            depth_rbo: 0,
+            color_texture: Some(color_texture),
+            size: size
+        };
+        unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer.fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer.fbo);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, framebuffer.get_texture_id(), 0);
+            gl::GenRenderbuffers(1, &mut framebuffer.depth_rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, framebuffer.depth_rbo);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, size[0], size[1]);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, framebuffer.depth_rbo);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+        }
+        check_opengl_error("Framebuffer::new")?;
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0) };
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(GraphicsError::FunctionFailure(format!("Framebuffer incomplete, status = {}", status)));
+        }
+        Ok(framebuffer)
+    }
+
+    // makes the framebuffer the render target and clears it to transparent,
+    // returns the previous viewport, which should be passed to unbind
+    pub fn bind(&self) -> [GLint; 4] {
+        let mut viewport: [GLint; 4] = [0; 4];
+        unsafe {
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            gl::Viewport(0, 0, self.size[0], self.size[1]);
+            gl::ClearColor(0., 0., 0., 0.);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+        viewport
+    }
+
+    pub fn unbind(&self, viewport: [GLint; 4]) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        }
+    }
+
+    pub fn get_size(&self) -> [GLsizei; 2] {
+        self.size
+    }
+
+    // generates the mipmaps of the rendered image and hands over the texture
+    pub fn into_texture(mut self) -> Result<Texture, OpenglError> {
+        let texture = self.color_texture.take().unwrap();
+        texture.activate();
+        unsafe { gl::GenerateMipmap(gl::TEXTURE_2D) };
+        texture.deactivate();
+        check_opengl_error("gl::GenerateMipmap")?;
+        Ok(texture)
+    }
+
+    fn get_texture_id(&self) -> GLuint {
+        match self.color_texture {
+            Some(ref texture) => texture.get_id(),
+            None => 0
+        }
+    }
+}
+
+fn create_color_texture(size: [GLsizei; 2]) -> Result<Texture, OpenglError> {
+    let mut id: GLuint = 0;
+    unsafe { gl::GenTextures(1, &mut id) };
+    check_opengl_error("gl::GenTextures")?;
+    let texture = Texture::new(id, gl::TEXTURE_2D);
+    texture.activate();
+    unsafe {
+        gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA8 as GLint, size[0], size[1], 0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null());
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as GLint);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+    }
+    texture.deactivate();
+    check_opengl_error("Framebuffer::create_color_texture")?;
+    Ok(texture)
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteRenderbuffers(1, &self.depth_rbo);
+            gl::DeleteFramebuffers(1, &self.fbo);
+        }
+        if let Err(e) = check_opengl_error("Framebuffer::drop") {
+            error!("{}", e);
+        }
+    }
+}
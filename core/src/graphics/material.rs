@@ -0,0 +1,100 @@
+use glm::Vector3;
+use serde::Deserialize;
+
+use crate::graphics::{ ShaderProgram, ShaderProgramBuilder, Texture, TextureBuilder, GraphicsError };
+use crate::Float;
+
+// uniforms every material shader must provide, they are set by the world for all materials
+pub const MATERIAL_SHADER_RESOURCES: [&str; 4] = ["view_projection", "view_pos", "light_pos", "fog_color"];
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(untagged)]
+pub enum UniformValue {
+    Float(Float),
+    Vec3([Float; 3])
+}
+
+// shader with its textures and uniform values, every material has its own shader program,
+// so the uniforms only need to be set once
+pub struct Material {
+    name: String,
+    shader: ShaderProgram,
+    textures: Vec<(String, Texture)>,
+    uniforms: Vec<(String, UniformValue)>
+}
+
+impl Material {
+    // the textures are given by their sampler name and image path, they get bound to consecutive units
+    pub fn new(name: &str, shader_dir: &str, textures: &[(String, String)], uniforms: &[(String, UniformValue)]) -> Result<Material, GraphicsError> {
+        info!("Creating material '{}', shader = '{}'", name, shader_dir);
+        let mut builder = ShaderProgramBuilder::new()
+            .add_vertex_shader((shader_dir.to_owned() + "/VertexShader.glsl").as_str())
+            .add_fragment_shader((shader_dir.to_owned() + "/FragmentShader.glsl").as_str());
+        for resource in MATERIAL_SHADER_RESOURCES.iter() {
+            builder = builder.add_resource(resource);
+        }
+        for (sampler, _) in textures.iter() {
+            builder = builder.add_resource(sampler);
+        }
+        for (uniform, _) in uniforms.iter() {
+            builder = builder.add_resource(uniform);
+        }
+        let shader = builder.finish()?;
+        shader.use_program();
+
+        let mut loaded_textures = Vec::new();
+        for (unit, (sampler, path)) in textures.iter().enumerate() {
+            debug!("Material '{}' uses '{}' as {}", name, path, sampler);
+            loaded_textures.push((sampler.clone(), TextureBuilder::new_2d(path).finish()?));
+            shader.set_resource_integer(sampler, unit as i32)?;
+        }
+        for (uniform, value) in uniforms.iter() {
+            match value {
+                UniformValue::Float(f) => shader.set_resource_float(uniform, *f)?,
+                UniformValue::Vec3(v) => shader.set_resource_vec3(uniform, &Vector3::new(v[0], v[1], v[2]))?
+            }
+        }
+
+        let material = Material {
+            name: name.to_string(),
+            shader: shader,
+            textures: loaded_textures,
+            uniforms: uniforms.to_vec()
+        };
+        Ok(material)
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_shader(&self) -> &ShaderProgram {
+        &self.shader
+    }
+
+    pub fn get_texture(&self, sampler: &str) -> Option<&Texture> {
+        self.textures.iter()
+            .find(|(name, _)| name == sampler)
+            .map(|(_, texture)| texture)
+    }
+
+    pub fn get_uniform(&self, uniform: &str) -> Option<UniformValue> {
+        self.uniforms.iter()
+            .find(|(name, _)| name == uniform)
+            .map(|(_, value)| *value)
+    }
+
+    // uses the shader and binds the textures, meshes can be rendered afterwards
+    pub fn bind(&self) {
+        self.shader.use_program();
+        for (unit, (_, texture)) in self.textures.iter().enumerate() {
+            texture.activate_unit(unit as u32);
+        }
+    }
+
+    pub fn unbind(&self) {
+        for (unit, (_, texture)) in self.textures.iter().enumerate() {
+            texture.deactivate_unit(unit as u32);
+        }
+    }
+}
@@ -1,12 +1,19 @@
 use std::convert::{TryFrom, TryInto};
 
+use glm::Matrix4;
+
 use super::vertex_buffer::{BUFFER_NORMAL, BUFFER_POSTION, BUFFER_UV};
-use super::{read_obj, triangles_to_buffers, MeshError, Triangle, VertexBuffer, VAO};
+use super::{
+    read_obj, read_obj_by_material, triangles_to_buffers, MeshError, Triangle, VertexBuffer, VAO,
+};
 use crate::graphics::GraphicsError;
 use crate::traits::{RenderInfo, Renderable};
+use crate::{BoundingSphere, Float, AABB};
 
 pub struct Mesh {
     vao: Option<VAO>,
+    bounds: AABB,
+    bounding_sphere: BoundingSphere,
 }
 
 impl Mesh {
@@ -14,6 +21,19 @@ impl Mesh {
         Mesh::try_from((read_obj(obj_path)?).as_slice())
     }
 
+    // one mesh per material used in the obj file, with the material name given by usemtl
+    // and the path of the material library given by mtllib
+    pub fn from_obj_by_material(
+        obj_path: &str,
+    ) -> Result<(Option<String>, Vec<(Option<String>, Mesh)>), MeshError> {
+        let (material_lib, groups) = read_obj_by_material(obj_path)?;
+        let mut meshes = Vec::new();
+        for (material, triangles) in groups {
+            meshes.push((material, Mesh::try_from(triangles.as_slice())?));
+        }
+        Ok((material_lib, meshes))
+    }
+
     pub fn from_obj_custom_buffers(obj_path: &str, buffer_flags: u8) -> Result<Mesh, MeshError> {
         let triangles = read_obj(obj_path)?;
         let (pos, uv, nm, index) = triangles_to_buffers(&triangles, buffer_flags);
@@ -35,12 +55,41 @@ impl Mesh {
         vb.try_into()
     }
 
+    pub fn enable_instancing(&mut self) -> Result<(), MeshError> {
+        if let Some(ref mut vao) = self.vao {
+            vao.enable_instancing()?;
+        }
+        Ok(())
+    }
+
+    pub fn render_instanced(&self, transforms: &[Matrix4<Float>]) -> Result<(), GraphicsError> {
+        match self.vao {
+            Some(ref vao) => vao.render_instanced(transforms),
+            None => Ok(()),
+        }
+    }
+
     pub fn get_vertex_count(&self) -> u32 {
         match self.vao {
             Some(ref vao) => vao.get_index_count(),
             _ => 0,
         }
     }
+
+    pub fn get_bounds(&self) -> AABB {
+        self.bounds
+    }
+
+    pub fn get_bounding_sphere(&self) -> BoundingSphere {
+        self.bounding_sphere
+    }
+
+    pub fn get_buffer_size(&self) -> usize {
+        match self.vao {
+            Some(ref vao) => vao.get_buffer_size(),
+            _ => 0,
+        }
+    }
 }
 
 impl Renderable for Mesh {
@@ -55,26 +104,30 @@ impl Renderable for Mesh {
 
 impl Default for Mesh {
     fn default() -> Self {
-        Self { vao: None }
+        Self {
+            vao: None,
+            bounds: AABB::default(),
+            bounding_sphere: BoundingSphere::default(),
+        }
     }
 }
 
 impl TryFrom<&[Triangle]> for Mesh {
     type Error = MeshError;
     fn try_from(triangles: &[Triangle]) -> Result<Self, Self::Error> {
-        let vb = VertexBuffer::from(triangles);
-        let mesh = Self {
-            vao: Some(vb.try_into()?),
-        };
-        Ok(mesh)
+        Self::try_from(VertexBuffer::from(triangles))
     }
 }
 
 impl TryFrom<VertexBuffer> for Mesh {
     type Error = MeshError;
     fn try_from(vb: VertexBuffer) -> Result<Self, Self::Error> {
+        let bounds = vb.calculate_bounds();
+        let bounding_sphere = vb.calculate_bounding_sphere();
         let mesh = Self {
             vao: Some(vb.try_into()?),
+            bounds: bounds,
+            bounding_sphere: bounding_sphere,
         };
         Ok(mesh)
     }
@@ -4,7 +4,9 @@ pub mod mesh;
 pub mod mesh_error;
 pub mod vao;
 pub mod vertex_buffer;
+pub mod shared_index_buffer;
 mod read_obj;
+mod read_mtl;
 mod utility;
 
 pub use self::vertex::Vertex;
@@ -13,5 +15,7 @@ pub use self::mesh::Mesh;
 pub use self::mesh_error::MeshError;
 pub use self::vao::VAO;
 pub use self::vertex_buffer::VertexBuffer;
-use self::read_obj::read_obj;
+pub use self::shared_index_buffer::SharedIndexBuffer;
+pub use self::read_mtl::{ read_mtl, MtlMaterial };
+use self::read_obj::{ read_obj, read_obj_by_material };
 use self::vertex_buffer::triangles_to_buffers;
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use crate::Float;
+use crate::file::{ read_file, FileError };
+
+// the parts of a material from a mtl file, which are used: the diffuse color and texture
+pub struct MtlMaterial {
+    name: String,
+    diffuse_color: [Float; 3],
+    diffuse_texture: Option<String>
+}
+
+impl MtlMaterial {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn get_diffuse_color(&self) -> [Float; 3] {
+        self.diffuse_color
+    }
+    // relative to the working directory
+    pub fn get_diffuse_texture(&self) -> Option<&str> {
+        self.diffuse_texture.as_ref().map(|path| path.as_str())
+    }
+}
+
+pub fn read_mtl(mtl_path: &str) -> Result<Vec<MtlMaterial>, FileError> {
+    let content = read_file(mtl_path)?;
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        if fields[0] == "newmtl" {
+            if fields.len() != 2 {
+                return Err(FileError::UnexpectedFormat(line.to_string()));
+            }
+            materials.push(MtlMaterial {
+                name: fields[1].to_string(),
+                diffuse_color: [1., 1., 1.],
+                diffuse_texture: None
+            });
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some(m) => m,
+            None => continue
+        };
+        match fields[0] {
+            "Kd" => {
+                if fields.len() != 4 {
+                    return Err(FileError::UnexpectedFormat(line.to_string()));
+                }
+                for i in 0..3 {
+                    material.diffuse_color[i] = fields[i + 1].parse()?;
+                }
+            },
+            "map_Kd" => {
+                // options before the path are not supported
+                if fields.len() != 2 {
+                    return Err(FileError::UnexpectedFormat(line.to_string()));
+                }
+                let path = match Path::new(mtl_path).parent() {
+                    Some(dir) => dir.join(fields[1]).to_string_lossy().into_owned(),
+                    None => fields[1].to_string()
+                };
+                material.diffuse_texture = Some(path);
+            },
+            _ => {}
+        }
+    }
+    debug!("Read mtl file '{}': materials = {}", mtl_path, materials.len());
+    Ok(materials)
+}
@@ -1,14 +1,25 @@
+use std::path::Path;
+
 use glm::{ Vector2, Vector3 };
 
 use crate::Float;
 use crate::file::{ read_file, FileError };
 use super::{ Triangle, Vertex };
 
+// faces of an obj file, which got the same material by usemtl, none for faces before any usemtl
+pub type MaterialGroup = (Option<String>, Vec<Triangle>);
+
 pub fn read_obj(obj_path: &str) -> Result<Vec<Triangle>, FileError> {
-    let (verts, uvs, normals, faces) = read_raw_content(obj_path)?;
+    let (_, groups) = read_obj_by_material(obj_path)?;
+    Ok(groups.into_iter().flat_map(|(_, triangles)| triangles).collect())
+}
+
+// also returns the path of the material library given by mtllib, relative to the working directory
+pub fn read_obj_by_material(obj_path: &str) -> Result<(Option<String>, Vec<MaterialGroup>), FileError> {
+    let (verts, uvs, normals, faces, material_lib) = read_raw_content(obj_path)?;
     debug!("Read obj file '{}': vertices = {}, uvs = {}, normals = {}, faces = {}", obj_path, verts.len(), uvs.len(), normals.len(), faces.len());
-    let mut triangles: Vec<Triangle> = Vec::new();
-    for face in faces {
+    let mut groups: Vec<MaterialGroup> = Vec::new();
+    for (material, face) in faces {
         let mut triangle = Triangle::default();
         for (i, indices) in face.iter().enumerate() {
             let mut vert = Vertex::default();
@@ -20,22 +31,32 @@ pub fn read_obj(obj_path: &str) -> Result<Vec<Triangle>, FileError> {
             triangle.set_vertex(vert, i);
         }
         triangle.update_normal();
-        triangles.push(triangle);
+        match groups.iter_mut().find(|(m, _)| *m == material) {
+            Some((_, triangles)) => triangles.push(triangle),
+            None => groups.push((material, vec![triangle]))
+        }
     }
+    let material_lib = material_lib.map(|lib| match Path::new(obj_path).parent() {
+        Some(dir) => dir.join(lib).to_string_lossy().into_owned(),
+        None => lib
+    });
 
-    Ok(triangles)
+    Ok((material_lib, groups))
 }
 
 fn read_raw_content(obj_path: &str) -> Result<(Vec<[Float; 3]>,
                                                Vec<[Float; 2]>,
                                                Vec<[Float; 3]>,
-                                               Vec<[[usize; 3]; 3]>),
+                                               Vec<(Option<String>, [[usize; 3]; 3])>,
+                                               Option<String>),
                                                FileError> {
     let content = read_file(obj_path)?;
     let mut verts: Vec<[Float; 3]> = Vec::new();
     let mut uvs: Vec<[Float; 2]> = Vec::new();
     let mut normals: Vec<[Float; 3]> = Vec::new();
-    let mut faces: Vec<[[usize; 3]; 3]> = Vec::new();
+    let mut faces: Vec<(Option<String>, [[usize; 3]; 3])> = Vec::new();
+    let mut material: Option<String> = None;
+    let mut material_lib: Option<String> = None;
     for line in content.lines() {
         let fields: Vec<&str> = line.split(" ").collect();
         match fields[0] {
@@ -79,10 +100,16 @@ fn read_raw_content(obj_path: &str) -> Result<(Vec<[Float; 3]>,
                                 sub_fields[1].parse()?,
                                 sub_fields[2].parse()?];
                 }
-                faces.push(face);
+                faces.push((material.clone(), face));
+            },
+            "usemtl" if fields.len() == 2 => {
+                material = Some(fields[1].to_string());
+            },
+            "mtllib" if fields.len() == 2 => {
+                material_lib = Some(fields[1].to_string());
             },
             _ => {}
         }
     }
-    Ok((verts, uvs, normals, faces))
+    Ok((verts, uvs, normals, faces, material_lib))
 }
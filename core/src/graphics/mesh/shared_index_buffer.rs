@@ -0,0 +1,81 @@
+use std::convert::TryInto;
+use std::mem::size_of;
+use std::sync::Mutex;
+
+use gl;
+use gl::types::GLuint;
+
+use crate::graphics::{ check_opengl_error, OpenglError };
+use super::utility::delete_vbos;
+
+// element buffer for meshes with the same topology, e.g. terrain grids, which gets bound into all their vaos.
+// The indices can be created on any thread, the buffer is uploaded by the first vao using it.
+pub struct SharedIndexBuffer {
+    index_list: Vec<GLuint>,
+    ebo: Mutex<Option<GLuint>>
+}
+
+impl SharedIndexBuffer {
+    pub fn new(index_list: Vec<GLuint>) -> SharedIndexBuffer {
+        SharedIndexBuffer {
+            index_list: index_list,
+            ebo: Mutex::new(None)
+        }
+    }
+
+    pub fn get_index_count(&self) -> usize {
+        self.index_list.len()
+    }
+
+    pub fn get_buffer_size(&self) -> usize {
+        self.index_list.len() * size_of::<GLuint>()
+    }
+
+    // needs the gl context, the buffer is only uploaded once
+    pub fn get_or_upload(&self) -> Result<GLuint, OpenglError> {
+        // the id is only set after a successful upload, so a poisoned lock still holds a valid state
+        let mut ebo = match self.ebo.lock() {
+            Ok(ebo) => ebo,
+            Err(poisoned) => poisoned.into_inner()
+        };
+        if let Some(id) = *ebo {
+            return Ok(id);
+        }
+        let mut id: GLuint = 0;
+        unsafe { gl::GenBuffers(1, &mut id) };
+        check_opengl_error("gl::GenBuffers")?;
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, id);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                self.get_buffer_size().try_into().unwrap(),
+                self.index_list.as_ptr() as * const _,
+                gl::STATIC_DRAW
+            );
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+        }
+        if let Err(e) = check_opengl_error("gl::BufferData") {
+            if let Err(new_err) = delete_vbos(&[id]) {
+                error!("Additional error: {}", new_err);
+            }
+            return Err(e);
+        }
+        debug!("Uploaded shared index buffer with {} indices", self.index_list.len());
+        *ebo = Some(id);
+        Ok(id)
+    }
+}
+
+impl Drop for SharedIndexBuffer {
+    fn drop(&mut self) {
+        let ebo = match self.ebo.get_mut() {
+            Ok(ebo) => *ebo,
+            Err(poisoned) => *poisoned.into_inner()
+        };
+        if let Some(id) = ebo {
+            if let Err(e) = delete_vbos(&[id]) {
+                error!("{}", e);
+            }
+        }
+    }
+}
@@ -1,31 +1,137 @@
 use gl;
 use gl::types::{GLenum, GLint, GLuint};
+use glm::Matrix4;
+use std::convert::TryInto;
+use std::ffi::c_void;
+use std::mem::size_of;
 use std::ptr;
+use std::sync::Arc;
 
 use super::utility::{delete_vao, delete_vbos};
-use crate::graphics::{check_opengl_error, GraphicsError};
+use super::SharedIndexBuffer;
+use crate::graphics::{check_opengl_error, GraphicsError, OpenglError};
 use crate::traits::{RenderInfo, Renderable};
+use crate::Float;
+
+// per-instance model matrices take the attribute indices 3 to 6, one column each
+pub const INSTANCE_ATTRIBUTE_INDEX: GLuint = 3;
 
 pub struct VAO {
     vao: GLuint,
     vbos: Vec<GLuint>,
     element_type: GLenum,
     index_count: GLint,
+    buffer_size: usize,
+    instance_vbo: Option<GLuint>,
+    // only kept, so the shared index buffer outlives the vao
+    _shared_index_buffer: Option<Arc<SharedIndexBuffer>>,
 }
 
 impl VAO {
-    pub fn new(vao: GLuint, vbos: &[GLuint], element_type: GLenum, index_count: GLint) -> Self {
+    pub fn new(
+        vao: GLuint,
+        vbos: &[GLuint],
+        element_type: GLenum,
+        index_count: GLint,
+        buffer_size: usize,
+        shared_index_buffer: Option<Arc<SharedIndexBuffer>>,
+    ) -> Self {
         VAO {
             vao: vao,
             vbos: vbos.into(),
             element_type: element_type,
             index_count: index_count,
+            buffer_size: buffer_size,
+            instance_vbo: None,
+            _shared_index_buffer: shared_index_buffer,
+        }
+    }
+
+    // attaches a buffer for per-instance model matrices, which gets refilled on every instanced draw
+    pub fn enable_instancing(&mut self) -> Result<(), OpenglError> {
+        if self.instance_vbo.is_some() {
+            return Ok(());
+        }
+        let mut vbo: GLuint = 0;
+        unsafe { gl::GenBuffers(1, &mut vbo) };
+        check_opengl_error("gl::GenBuffers")?;
+        self.instance_vbo = Some(vbo);
+
+        let stride = size_of::<Matrix4<Float>>();
+        let column_size = stride / 4;
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            for column in 0..4 {
+                let index = INSTANCE_ATTRIBUTE_INDEX + column as GLuint;
+                gl::EnableVertexAttribArray(index);
+                gl::VertexAttribPointer(
+                    index,
+                    4,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    stride.try_into().unwrap(),
+                    (column * column_size) as *const c_void,
+                );
+                gl::VertexAttribDivisor(index, 1);
+            }
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+        check_opengl_error("VAO::enable_instancing")
+    }
+
+    pub fn is_instanced(&self) -> bool {
+        self.instance_vbo.is_some()
+    }
+
+    // draws the vao once per model matrix
+    pub fn render_instanced(&self, transforms: &[Matrix4<Float>]) -> Result<(), GraphicsError> {
+        let vbo = match self.instance_vbo {
+            Some(vbo) => vbo,
+            None => {
+                return Err(GraphicsError::FunctionFailure(
+                    "VAO::render_instanced without instance buffer".to_string(),
+                ))
+            }
+        };
+        if transforms.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (transforms.len() * size_of::<Matrix4<Float>>())
+                    .try_into()
+                    .unwrap(),
+                transforms.as_ptr() as *const c_void,
+                gl::STREAM_DRAW,
+            );
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(self.vao);
+            gl::DrawElementsInstanced(
+                self.element_type,
+                self.index_count,
+                gl::UNSIGNED_INT,
+                ptr::null(),
+                transforms.len().try_into().unwrap(),
+            );
+            gl::BindVertexArray(0);
+        }
+        match check_opengl_error("VAO::render_instanced") {
+            Ok(_) => Ok(()),
+            Err(e) => Err(GraphicsError::from(e)),
         }
     }
 
     pub fn get_index_count(&self) -> u32 {
         self.index_count as u32
     }
+
+    pub fn get_buffer_size(&self) -> usize {
+        self.buffer_size
+    }
 }
 
 impl Renderable for VAO {
@@ -49,6 +155,11 @@ impl Renderable for VAO {
 
 impl Drop for VAO {
     fn drop(&mut self) {
+        if let Some(vbo) = self.instance_vbo {
+            if let Err(e) = delete_vbos(&[vbo]) {
+                error!("{}", e);
+            }
+        }
         if let Err(e) = delete_vbos(&self.vbos) {
             error!("{}", e);
         }
@@ -14,6 +14,7 @@ enum UV {
 pub struct Vertex {
     pos: Vector3<Float>,
     uv: UV,
+    normal: Option<Vector3<Float>>,
 }
 
 impl Vertex {
@@ -36,6 +37,11 @@ impl Vertex {
         }
     }
 
+    // none, if the vertex should use the normal of its triangle
+    pub fn get_normal(&self) -> Option<Vector3<Float>> {
+        self.normal
+    }
+
     pub fn set_pos(&mut self, new_pos: Vector3<Float>) {
         self.pos = new_pos;
     }
@@ -52,6 +58,10 @@ impl Vertex {
         }
     }
 
+    pub fn set_normal(&mut self, new_normal: Vector3<Float>) {
+        self.normal = Some(new_normal);
+    }
+
     //extends uv to 3d, if 2d uv existing
     pub fn set_uv_layer(&mut self, layer: u32) {
         let new_uv = match self.uv {
@@ -81,6 +91,7 @@ impl Default for Vertex {
         Vertex {
             pos: Vector3::from_s(0.),
             uv: UV::Dim2(Vector2::from_s(0.)),
+            normal: None,
         }
     }
 }
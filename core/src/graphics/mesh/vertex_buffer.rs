@@ -1,13 +1,15 @@
 use std::{ ffi::c_void, mem::size_of };
 use std::collections::btree_map::{ BTreeMap, Entry };
 use std::convert::TryInto;
+use std::sync::Arc;
 
 use gl;
+use glm::Vector3;
 use gl::types::{ GLuint, GLint, GLenum, GLsizeiptr };
 
-use crate::Float;
+use crate::{ Float, AABB, BoundingSphere };
 use crate::graphics::{ check_opengl_error, OpenglError };
-use super::{ VAO, Triangle, Vertex, MeshError, utility::{ delete_vao, delete_vbos }};
+use super::{ VAO, Triangle, Vertex, MeshError, SharedIndexBuffer, utility::{ delete_vao, delete_vbos }};
 
 pub const BUFFER_POSTION: u8 = 1 << 1;
 pub const BUFFER_UV: u8 = 1 << 2;
@@ -16,6 +18,8 @@ pub const BUFFER_NORMAL: u8 = 1 << 3;
 pub struct VertexBuffer {
     buffer_list: Vec<Buffer>,
     index_list: Vec<GLuint>,
+    // used instead of the own index list
+    shared_index_buffer: Option<Arc<SharedIndexBuffer>>,
     element_type: GLenum
 }
 
@@ -35,8 +39,57 @@ impl VertexBuffer {
 
     pub fn set_index_buffer(&mut self, index_data: Vec<GLuint>) {
         self.index_list = index_data;
+        self.shared_index_buffer = None;
+    }
+
+    // for meshes with the same topology, e.g. terrain grids
+    pub fn set_shared_index_buffer(&mut self, index_buffer: Arc<SharedIndexBuffer>) {
+        self.index_list = Vec::new();
+        self.shared_index_buffer = Some(index_buffer);
+    }
+
+    pub fn get_index_count(&self) -> usize {
+        match self.shared_index_buffer {
+            Some(ref shared) => shared.get_index_count(),
+            None => self.index_list.len()
+        }
+    }
+
+    // a shared index buffer isn't counted, it gets uploaded only once for all meshes using it
+    pub fn get_buffer_size(&self) -> usize {
+        let mut size = self.index_list.len() * size_of::<GLuint>();
+        for buffer in self.buffer_list.iter() {
+            match buffer {
+                Buffer::Float { data, .. } => size += data.len() * size_of::<Float>()
+            }
+        }
+        size
+    }
+
+    // bounds of the position buffer, which is expected at attribute index 0
+    pub fn calculate_bounds(&self) -> AABB {
+        for buffer in self.buffer_list.iter() {
+            match buffer {
+                Buffer::Float { data, attribute_index: 0, element_count: 3 } => {
+                    return AABB::from_points(data.chunks(3).map(|p| Vector3::new(p[0], p[1], p[2])));
+                }
+                _ => {}
+            }
+        }
+        AABB::default()
+    }
+
+    pub fn calculate_bounding_sphere(&self) -> BoundingSphere {
+        for buffer in self.buffer_list.iter() {
+            match buffer {
+                Buffer::Float { data, attribute_index: 0, element_count: 3 } => {
+                    return BoundingSphere::from_points(data.chunks(3).map(|p| Vector3::new(p[0], p[1], p[2])));
+                }
+                _ => {}
+            }
+        }
+        BoundingSphere::default()
     }
-   
 }
 
 impl Default for VertexBuffer {
@@ -44,6 +97,7 @@ impl Default for VertexBuffer {
         VertexBuffer {
             buffer_list: Vec::new(),
             index_list: Vec::new(),
+            shared_index_buffer: None,
             element_type: gl::TRIANGLES
         }
     }
@@ -53,8 +107,21 @@ impl TryInto<VAO> for VertexBuffer {
     type Error = MeshError;
 
     fn try_into(self) -> Result<VAO, Self::Error> {
-        let vbo_ids = create_vbos(&self.index_list, &self.buffer_list)?;
-        let vao_id = match create_vao(&vbo_ids, &self.buffer_list) {
+        // the vao only owns its own index buffer, so a shared one isn't in its vbo list
+        let shared_ebo = match self.shared_index_buffer {
+            Some(ref shared) => Some(shared.get_or_upload()?),
+            None => None
+        };
+        let owned_index_list = match shared_ebo {
+            Some(_) => None,
+            None => Some(self.index_list.as_slice())
+        };
+        let vbo_ids = create_vbos(owned_index_list, &self.buffer_list)?;
+        let (index_vbo, attribute_vbos) = match shared_ebo {
+            Some(ebo) => (ebo, &vbo_ids[..]),
+            None => (vbo_ids[0], &vbo_ids[1..])
+        };
+        let vao_id = match create_vao(index_vbo, attribute_vbos, &self.buffer_list) {
             Ok(id) => id,
             Err(e) => {
                 if let Err(new_err) = delete_vbos(&vbo_ids) {
@@ -68,7 +135,9 @@ impl TryInto<VAO> for VertexBuffer {
             vao_id,
             &vbo_ids,
             self.element_type,
-            self.index_list.len().try_into().unwrap()
+            self.get_index_count().try_into().unwrap(),
+            self.get_buffer_size(),
+            self.shared_index_buffer.clone()
         );
         Ok(vao)
     }
@@ -112,7 +181,8 @@ pub fn triangles_to_buffers(triangles: &[Triangle], buffer_flags: u8) ->
                         }
                     }
                     if buffer_flags & BUFFER_NORMAL != 0 {
-                        normal_buffer.extend(triangle.get_normal().as_array());
+                        let normal = vertex.get_normal().unwrap_or(triangle.get_normal());
+                        normal_buffer.extend(normal.as_array());
                     }
                     index_buffer.push(new_index);
                     v.insert(new_index);
@@ -145,9 +215,9 @@ impl From<&[Triangle]> for VertexBuffer {
     }
 }
 
-fn create_vao(vbos: &[GLuint], buffer_list: &[Buffer]) -> Result<GLuint, OpenglError> {
-    debug_assert!(vbos.len() == 1 + buffer_list.len()); // 1+ because vbo @ 0 is index vbo
-    debug_assert!(vbos.iter().all(|v| *v != 0));
+fn create_vao(index_vbo: GLuint, vbos: &[GLuint], buffer_list: &[Buffer]) -> Result<GLuint, OpenglError> {
+    debug_assert!(vbos.len() == buffer_list.len());
+    debug_assert!(index_vbo != 0 && vbos.iter().all(|v| *v != 0));
     let mut vao: GLuint = 0;
 
     unsafe { gl::GenVertexArrays(1, &mut vao); }
@@ -164,7 +234,7 @@ fn create_vao(vbos: &[GLuint], buffer_list: &[Buffer]) -> Result<GLuint, OpenglE
         }
     }
 
-    for (vbo, buffer) in vbos.iter().skip(1).zip(buffer_list.iter()) {
+    for (vbo, buffer) in vbos.iter().zip(buffer_list.iter()) {
         match buffer {
             Buffer::Float { attribute_index, element_count, .. } => {
                 let result = assign_buffer_to_vao(
@@ -183,7 +253,7 @@ fn create_vao(vbos: &[GLuint], buffer_list: &[Buffer]) -> Result<GLuint, OpenglE
         }
     }
 
-    unsafe { gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, vbos[0]); }
+    unsafe { gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index_vbo); }
 
     if let Err(e) = check_opengl_error("gl::BindBuffer") {
         if let Err(new_err) = delete_vao(vao) {
@@ -199,7 +269,7 @@ fn create_vao(vbos: &[GLuint], buffer_list: &[Buffer]) -> Result<GLuint, OpenglE
         return Err(e);
     }
 
-    if let Err(e) = disable_vertex_attributes(vbos.len()) {
+    if let Err(e) = disable_vertex_attributes(vbos.len() + 1) {
         if let Err(new_err) = delete_vao(vao) {
             error!("Additional error: {}", new_err);
         }
@@ -243,10 +313,12 @@ fn assign_buffer_to_vao(vbo: GLuint, index: GLuint, size: GLint, data_type: GLen
     Ok(())
 }
 
-fn create_vbos(index_list: &[GLuint], buffer_list: &[Buffer]) -> Result<Vec<GLuint>, OpenglError> {
-    let vbos = create_vbo_ids(buffer_list.len() + 1)?;
+// the index vbo comes first, if there is an index list
+fn create_vbos(index_list: Option<&[GLuint]>, buffer_list: &[Buffer]) -> Result<Vec<GLuint>, OpenglError> {
+    let index_vbo_count = if index_list.is_some() { 1 } else { 0 };
+    let vbos = create_vbo_ids(buffer_list.len() + index_vbo_count)?;
 
-    for (buffer, vbo) in buffer_list.iter().zip(vbos.iter().skip(1)) {
+    for (buffer, vbo) in buffer_list.iter().zip(vbos.iter().skip(index_vbo_count)) {
         match buffer {
             Buffer::Float { data, .. } => {
                 let buffer_size = data.len() * size_of::<Float>();
@@ -266,6 +338,10 @@ fn create_vbos(index_list: &[GLuint], buffer_list: &[Buffer]) -> Result<Vec<GLui
         }
     }
 
+    let index_list = match index_list {
+        Some(index_list) => index_list,
+        None => return Ok(vbos)
+    };
     let buffer_size = index_list.len() * size_of::<GLuint>();
     let result = fill_vbo(
         vbos[0],
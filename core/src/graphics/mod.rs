@@ -6,6 +6,8 @@ pub mod transformation;
 pub mod version;
 pub mod graphics_error;
 pub mod model;
+pub mod framebuffer;
+pub mod material;
 mod opengl_error;
 mod opengl_string;
 
@@ -16,6 +18,8 @@ pub use self::texture::TextureBuilder;
 pub use self::mesh::Mesh;
 pub use self::mesh::triangle::Triangle;
 pub use self::model::Model;
+pub use self::framebuffer::Framebuffer;
+pub use self::material::{ Material, UniformValue };
 pub use self::projection::Projection;
 pub use self::transformation::{ create_transformation_matrix, create_translation_matrix, create_rotation_matrix, create_scale_matrix, create_direction };
 pub use self::graphics_error::GraphicsError;
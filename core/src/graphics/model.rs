@@ -8,10 +8,13 @@ use super::create_transformation_matrix;
 use crate::traits::{Rotatable, Scalable, Translatable};
 use crate::Float;
 
+#[derive(Clone)]
 pub struct Model {
     position: Vector3<Float>,
     rotation: Vector3<Float>,
     scale: Vector3<Float>,
+    // world matrix of the parent scene node, boxed as most models have none
+    parent_matrix: Option<Box<Matrix4<Float>>>,
     matrix: Matrix4<Float>,
 }
 
@@ -22,8 +25,23 @@ impl Model {
     pub fn get_matrix_ref(&self) -> &Matrix4<Float> {
         &self.matrix
     }
+    // the translation in world space, including the parent matrix
+    pub fn get_world_translation(&self) -> Vector3<Float> {
+        self.matrix[3].truncate(3)
+    }
+    pub fn get_parent_matrix(&self) -> Option<&Matrix4<Float>> {
+        self.parent_matrix.as_ref().map(|matrix| matrix.as_ref())
+    }
+    pub fn set_parent_matrix(&mut self, parent_matrix: Option<&Matrix4<Float>>) {
+        self.parent_matrix = parent_matrix.map(|matrix| Box::new(*matrix));
+        self.update_matrix();
+    }
     fn update_matrix(&mut self) {
-        self.matrix = create_transformation_matrix(self.position, self.rotation, self.scale);
+        let local = create_transformation_matrix(self.position, self.rotation, self.scale);
+        self.matrix = match self.parent_matrix {
+            Some(ref parent) => **parent * local,
+            None => local,
+        };
     }
 }
 
@@ -33,6 +51,7 @@ impl Default for Model {
             position: Vector3::from_s(0.),
             rotation: Vector3::from_s(0.),
             scale: Vector3::from_s(1.),
+            parent_matrix: None,
             matrix: Matrix4::one(),
         };
         model.update_matrix();
@@ -16,6 +16,10 @@ impl Texture {
         }
     }
 
+    pub fn get_id(&self) -> GLuint {
+        self.id
+    }
+
     pub fn activate(&self) {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
@@ -28,6 +32,22 @@ impl Texture {
         unsafe { gl::BindTexture(self.tex_type, 0) }
         // TODO: maybe add check_opengl_error
     }
+
+    // for shaders sampling several textures, the unit is an offset to gl::TEXTURE0
+    pub fn activate_unit(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(self.tex_type, self.id);
+        }
+    }
+
+    pub fn deactivate_unit(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(self.tex_type, 0);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+    }
 }
 
 impl Drop for Texture {
@@ -104,6 +104,14 @@ pub fn fill_texture(texture_type: &TextureType, img: RgbaImage) -> Result<(), Gr
                 );
             }
             check_opengl_error("gl::TexSubImage2D(gl::TEXTURE_2D)")?;
+            unsafe {
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as GLint);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as GLint);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as GLint);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+            }
+            check_opengl_error("gl::GenerateMipmap(gl::TEXTURE_2D)")?;
         },
         TextureType::Array2D { index_list, size } => {
             trace!("Filling gl::TEXTURE_2D_ARRAY");
@@ -123,6 +131,9 @@ pub fn add_subimages(img: &RgbaImage, sub_size: [u32; 2], index_list: &[[u32; 3]
         let origin = [index[0] * sub_size[0],
                       index[1] * sub_size[1]];
         trace!("Adding subimage, origin = {}/{}, layer = {}", origin[0], origin[1], index[2]);
+        if origin[0] + sub_size[0] > img.width() || origin[1] + sub_size[1] > img.height() {
+            return Err(GraphicsError::FunctionFailure(format!("Subimage at {}/{} outside of the image", index[0], index[1])));
+        }
         let sub_img = img.view(origin[0], origin[1], sub_size[0], sub_size[1]).to_image();
         let pixels: Vec<u8> = sub_img.into_raw();
         add_subimage(
@@ -11,17 +11,21 @@ pub mod object;
 pub mod graphics;
 pub mod config;
 pub mod file;
+pub mod aabb;
 pub mod bounding_box;
+pub mod bounding_sphere;
 pub mod camera;
 pub mod cmp;
 pub mod core;
 pub mod distance;
 pub mod float;
 pub mod format;
+pub mod frustum;
 pub mod player;
 pub mod skybox;
 pub mod sun;
 pub mod timer;
+pub mod scene;
 pub mod update_error;
 pub mod core_error;
 mod window;
@@ -29,12 +33,16 @@ mod window;
 pub use self::core::Core;
 pub use self::core_error::CoreError;
 
+pub use self::aabb::AABB;
 pub use self::bounding_box::BoundingBox;
+pub use self::bounding_sphere::BoundingSphere;
+pub use self::frustum::Frustum;
 pub use self::camera::Camera;
 pub use self::player::Player;
 pub use self::skybox::Skybox;
 pub use self::timer::Timer;
 pub use self::sun::Sun;
+pub use self::scene::{ SceneGraph, SceneNode, NodeId };
 
 pub use self::traits::Translatable;
 pub use self::traits::Rotatable;
@@ -0,0 +1,66 @@
+use glm::{ Vector2, Vector3, length };
+
+use crate::{ Float, AABB };
+
+// collision shape of a placed object in world space
+#[derive(Copy, Clone)]
+pub enum Collider {
+    Cylinder { center: Vector2<Float>, radius: Float, min_z: Float, max_z: Float },
+    Box(AABB)
+}
+
+impl Collider {
+    pub fn get_bounds(&self) -> AABB {
+        match *self {
+            Collider::Cylinder { center, radius, min_z, max_z } => {
+                AABB::new((center - radius).extend(min_z), (center + radius).extend(max_z))
+            },
+            Collider::Box(aabb) => aabb
+        }
+    }
+
+    // horizontal offset, which moves an upright cylinder standing at the position out of the collider.
+    // Movement along the collider is kept, so applying it to the target of a move slides along the surface.
+    pub fn get_push(&self, pos: Vector3<Float>, radius: Float, height: Float) -> Option<Vector2<Float>> {
+        let bounds = self.get_bounds();
+        if pos.z >= bounds.get_max().z || pos.z + height <= bounds.get_min().z {
+            return None;
+        }
+        let pos_xy = pos.truncate(2);
+        match *self {
+            Collider::Cylinder { center, radius: collider_radius, .. } => {
+                push_from_point(pos_xy - center, radius + collider_radius)
+            },
+            Collider::Box(aabb) => {
+                let (min, max) = (aabb.get_min().truncate(2), aabb.get_max().truncate(2));
+                let closest = Vector2::new(Float::min(Float::max(pos_xy.x, min.x), max.x),
+                                           Float::min(Float::max(pos_xy.y, min.y), max.y));
+                if closest != pos_xy {
+                    return push_from_point(pos_xy - closest, radius);
+                }
+                // inside of the box, leaving it by the nearest side
+                let exits = [
+                    Vector2::new(min.x - pos_xy.x - radius, 0.),
+                    Vector2::new(max.x - pos_xy.x + radius, 0.),
+                    Vector2::new(0., min.y - pos_xy.y - radius),
+                    Vector2::new(0., max.y - pos_xy.y + radius)
+                ];
+                exits.iter()
+                    .min_by(|a, b| length(**a).total_cmp(&length(**b)))
+                    .cloned()
+            }
+        }
+    }
+}
+
+// offset moving a point at the difference from the collider to the minimum distance
+fn push_from_point(diff: Vector2<Float>, min_distance: Float) -> Option<Vector2<Float>> {
+    let distance = length(diff);
+    if distance >= min_distance {
+        None
+    } else if distance > 1e-4 {
+        Some(diff * ((min_distance - distance) / distance))
+    } else {
+        Some(Vector2::new(min_distance, 0.))
+    }
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use glm::{ Vector2, Vector3, Vector4, Matrix4, length };
+
+use crate::{ Float, AABB };
+use super::Collider;
+
+// collision shape of a prototype in its local coordinates, as declared in the prototype file,
+// missing extents are taken from the bounds of the mesh
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CollisionShape {
+    // upright around the local z axis, starting at the origin, e.g. for tree trunks
+    Cylinder { radius: Float, height: Option<Float> },
+    Box { min: Option<[Float; 3]>, max: Option<[Float; 3]> },
+    None
+}
+
+impl CollisionShape {
+    // the collider in the space the matrix transforms to, cylinders stay upright
+    // and boxes get axis aligned, so tilted objects collide a bit larger than they are
+    pub fn to_collider(&self, bounds: &AABB, matrix: &Matrix4<Float>) -> Option<Collider> {
+        match *self {
+            CollisionShape::Cylinder { radius, height } => {
+                let origin = *matrix * Vector4::new(0., 0., 0., 1.);
+                let horizontal_scale = Float::max(length(matrix[0].truncate(3)), length(matrix[1].truncate(3)));
+                let vertical_scale = length(matrix[2].truncate(3));
+                let height = height.unwrap_or(bounds.get_max().z);
+                Some(Collider::Cylinder {
+                    center: Vector2::new(origin.x, origin.y),
+                    radius: radius * horizontal_scale,
+                    min_z: origin.z,
+                    max_z: origin.z + height * vertical_scale
+                })
+            },
+            CollisionShape::Box { min, max } => {
+                let min = min.map(|m| Vector3::new(m[0], m[1], m[2])).unwrap_or(bounds.get_min());
+                let max = max.map(|m| Vector3::new(m[0], m[1], m[2])).unwrap_or(bounds.get_max());
+                Some(Collider::Box(AABB::new(min, max).transform(matrix)))
+            },
+            CollisionShape::None => None
+        }
+    }
+}
+
+impl Default for CollisionShape {
+    fn default() -> CollisionShape {
+        CollisionShape::None
+    }
+}
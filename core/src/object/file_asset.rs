@@ -1,20 +1,69 @@
 use serde::Deserialize;
 
-#[derive(Deserialize)]
+use crate::Float;
+use super::{ SpawnRule, CollisionShape };
+
+#[derive(Deserialize, Clone)]
 pub struct FileAsset {
     name: String,
-    lod0: String,
-    lod1: String
+    // ordered from the most detailed mesh on
+    #[serde(default)]
+    lods: Vec<FileLod>,
+    // older format with exactly two lods, used if no lod list is given
+    lod0: Option<String>,
+    lod1: Option<String>,
+    impostor_distance: Option<Float>,
+    // for faces, which have no material by usemtl
+    material: Option<String>,
+    spawn: Option<SpawnRule>,
+    #[serde(default)]
+    collision: CollisionShape
+}
+
+#[derive(Deserialize, Clone)]
+pub struct FileLod {
+    mesh: String,
+    // camera distance, from which on the lod is used, missing for the default
+    distance: Option<Float>
 }
 
 impl FileAsset {
     pub fn get_name(&self) -> &str {
         &self.name
     }
-    pub fn get_lod0_path(&self) -> &str {
-        &self.lod0
+    pub fn get_lods(&self) -> Vec<FileLod> {
+        if !self.lods.is_empty() {
+            return self.lods.clone();
+        }
+        self.lod0.iter()
+            .chain(self.lod1.iter())
+            .map(|path| FileLod { mesh: path.clone(), distance: None })
+            .collect()
+    }
+    pub fn get_impostor_distance(&self) -> Option<Float> {
+        self.impostor_distance
+    }
+    pub fn get_material(&self) -> Option<&str> {
+        self.material.as_ref().map(|name| name.as_str())
+    }
+    pub fn get_spawn_rule(&self) -> Option<&SpawnRule> {
+        self.spawn.as_ref()
     }
-    pub fn get_lod1_path(&self) -> &str {
-        &self.lod1
+    pub fn get_collision_shape(&self) -> CollisionShape {
+        self.collision
     }
-}
\ No newline at end of file
+    pub fn prepend_root_dir(&mut self, root_dir: &str) {
+        for path in self.lods.iter_mut().map(|lod| &mut lod.mesh).chain(self.lod0.iter_mut()).chain(self.lod1.iter_mut()) {
+            path.insert_str(0, root_dir);
+        }
+    }
+}
+
+impl FileLod {
+    pub fn get_mesh_path(&self) -> &str {
+        &self.mesh
+    }
+    pub fn get_distance(&self) -> Option<Float> {
+        self.distance
+    }
+}
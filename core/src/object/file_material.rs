@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::graphics::{ Material, UniformValue, GraphicsError, mesh::MtlMaterial };
+
+// material file, the textures are given by their sampler names in the shader
+#[derive(Deserialize, Clone)]
+pub struct FileMaterial {
+    shader: String,
+    #[serde(default)]
+    textures: BTreeMap<String, String>,
+    #[serde(default)]
+    uniforms: BTreeMap<String, UniformValue>
+}
+
+impl FileMaterial {
+    // materials of mtl files keep everything else of this one,
+    // only the diffuse texture and color get replaced
+    pub fn with_mtl(&self, mtl: &MtlMaterial) -> FileMaterial {
+        let mut material = self.clone();
+        if let Some(path) = mtl.get_diffuse_texture() {
+            material.textures.insert("diffuse_texture".to_string(), path.to_string());
+        }
+        material.uniforms.insert("diffuse_color".to_string(), UniformValue::Vec3(mtl.get_diffuse_color()));
+        material
+    }
+
+    pub fn get_texture_paths(&self) -> impl Iterator<Item = &String> {
+        self.textures.values()
+    }
+
+    pub fn create_material(&self, name: &str) -> Result<Material, GraphicsError> {
+        let textures: Vec<(String, String)> = self.textures.iter()
+            .map(|(sampler, path)| (sampler.clone(), path.clone()))
+            .collect();
+        let uniforms: Vec<(String, UniformValue)> = self.uniforms.iter()
+            .map(|(uniform, value)| (uniform.clone(), *value))
+            .collect();
+        Material::new(name, &self.shader, &textures, &uniforms)
+    }
+}
@@ -5,8 +5,10 @@ use super::FileAsset;
 #[derive(Deserialize)]
 pub struct FilePrototype {
     root_dir: String,
+    // materials are looked up by their name in this directory, the root dir without it
+    #[serde(default)]
+    material_dir: String,
     asset_list: Vec<FileAsset>,
-
 }
 
 pub struct PrototypeIterator {
@@ -18,6 +20,9 @@ impl FilePrototype {
     pub fn get_root_dir(&self) -> &str {
         &self.root_dir
     }
+    pub fn get_material_dir(&self) -> &str {
+        &self.material_dir
+    }
     pub fn get_asset(&self, index: usize) -> Option<&FileAsset> {
         self.asset_list.get(index)
     }
@@ -25,11 +30,16 @@ impl FilePrototype {
         if !self.root_dir.ends_with("/") {
             self.root_dir.push('/');
         }
+        if self.material_dir.is_empty() {
+            self.material_dir = self.root_dir.clone();
+        } else if !self.material_dir.ends_with("/") {
+            self.material_dir.push('/');
+        }
     }
 }
 
 impl IntoIterator for FilePrototype {
-    type Item = (String, String, String);
+    type Item = FileAsset;
     type IntoIter = PrototypeIterator;
 
     fn into_iter(mut self) -> Self::IntoIter {
@@ -42,14 +52,13 @@ impl IntoIterator for FilePrototype {
 }
 
 impl Iterator for PrototypeIterator {
-    type Item = (String, String, String);
+    type Item = FileAsset;
     
     fn next(&mut self) -> Option<Self::Item> {
         match self.prototype_file.get_asset(self.curr_index) {
             Some(asset) => {
-                let result = (asset.get_name().to_owned(),
-                              self.prototype_file.get_root_dir().to_owned() + asset.get_lod0_path(),
-                              self.prototype_file.get_root_dir().to_owned() + asset.get_lod1_path());
+                let mut result = asset.clone();
+                result.prepend_root_dir(self.prototype_file.get_root_dir());
                 self.curr_index += 1;
                 Some(result)
             },
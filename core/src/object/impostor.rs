@@ -0,0 +1,59 @@
+use std::convert::TryFrom;
+
+use glm::Matrix4;
+
+use crate::graphics::{ Texture, Mesh, GraphicsError, mesh::VertexBuffer };
+use crate::traits::RenderInfo;
+use crate::Float;
+
+// camera facing billboard, which shows one of several pictures of a mesh taken around its z axis,
+// the pictures are stored side by side in the atlas, starting at the local x axis counter clockwise
+pub struct Impostor {
+    atlas: Texture,
+    quad: Mesh,
+    view_count: u32,
+    half_size: Float,
+    center_height: Float
+}
+
+impl Impostor {
+    pub fn new(atlas: Texture, view_count: u32, half_size: Float, center_height: Float) -> Result<Impostor, GraphicsError> {
+        let impostor = Impostor {
+            atlas: atlas,
+            quad: create_quad()?,
+            view_count: view_count,
+            half_size: half_size,
+            center_height: center_height
+        };
+        Ok(impostor)
+    }
+
+    pub fn get_view_count(&self) -> u32 {
+        self.view_count
+    }
+
+    // the active shader builds the billboards from the model matrices, selecting the picture by the view angle
+    pub fn render_instanced(&self, info: &mut RenderInfo, transforms: &[Matrix4<Float>]) -> Result<(), GraphicsError> {
+        let shader = info.get_active_shader();
+        shader.set_resource_integer("view_count", self.view_count as i32)?;
+        shader.set_resource_float("half_size", self.half_size)?;
+        shader.set_resource_float("center_height", self.center_height)?;
+        self.atlas.activate();
+        let result = self.quad.render_instanced(transforms);
+        self.atlas.deactivate();
+        result
+    }
+}
+
+// unit quad in the xy plane, the corners get placed by the impostor shader
+fn create_quad() -> Result<Mesh, GraphicsError> {
+    let mut vb = VertexBuffer::default();
+    vb.add_float_buffer(vec![-1., -1., 0.,
+                             1., -1., 0.,
+                             1., 1., 0.,
+                             -1., 1., 0.], 0, 3);
+    vb.set_index_buffer(vec![0, 1, 2, 0, 2, 3]);
+    let mut quad = Mesh::try_from(vb)?;
+    quad.enable_instancing()?;
+    Ok(quad)
+}
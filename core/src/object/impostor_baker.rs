@@ -0,0 +1,110 @@
+use std::f32::consts::PI;
+
+use gl;
+use gl::types::GLint;
+use glm::{ Matrix4, Vector3, Vector4 };
+use num_traits::One;
+
+use crate::graphics::{ Framebuffer, ShaderProgram, ShaderProgramBuilder, GraphicsError, UniformValue };
+use crate::{ Float, AABB };
+use super::{ Impostor, MeshPart };
+
+// renders meshes from several directions around their z axis into an atlas at load time,
+// only the diffuse texture and color of their materials are used
+pub struct ImpostorBaker {
+    shader: ShaderProgram,
+    resolution: u32,
+    view_count: u32
+}
+
+impl ImpostorBaker {
+    pub fn new(shader_dir: &str, resolution: u32, view_count: u32) -> Result<ImpostorBaker, GraphicsError> {
+        debug_assert!(resolution > 0 && view_count > 0);
+        let shader = ShaderProgramBuilder::new()
+            .add_vertex_shader((shader_dir.to_owned() + "/VertexShader.glsl").as_str())
+            .add_fragment_shader((shader_dir.to_owned() + "/FragmentShader.glsl").as_str())
+            .add_resource("diffuse_texture")
+            .add_resource("diffuse_color")
+            .add_resource("view_projection")
+            .finish()?;
+        shader.use_program();
+        shader.set_resource_integer("diffuse_texture", 0)?;
+        info!("Impostor baker uses {} views with {}x{} pixels", view_count, resolution, resolution);
+        let baker = ImpostorBaker {
+            shader: shader,
+            resolution: resolution,
+            view_count: view_count
+        };
+        Ok(baker)
+    }
+
+    pub fn bake(&self, parts: &[MeshPart]) -> Result<Impostor, GraphicsError> {
+        debug_assert!(!parts.is_empty());
+        let bounds = parts.iter().skip(1)
+            .map(|part| part.get_mesh().get_bounds())
+            .fold(parts[0].get_mesh().get_bounds(), |a, b| a.merge(&b));
+        let (half_size, center_height) = get_billboard_extent(&bounds);
+        let framebuffer = Framebuffer::new([self.resolution * self.view_count, self.resolution])?;
+        let transforms = [Matrix4::<Float>::one()];
+
+        self.shader.use_program();
+        let viewport = framebuffer.bind();
+        let mut result = Ok(());
+        for view in 0..self.view_count {
+            let angle = 2. * PI * view as Float / self.view_count as Float;
+            let view_projection = create_view_projection_matrix(angle, half_size, center_height);
+            unsafe {
+                gl::Viewport((view * self.resolution) as GLint, 0, self.resolution as GLint, self.resolution as GLint);
+            }
+            result = self.shader.set_resource_mat4("view_projection", &view_projection)
+                .map_err(GraphicsError::from)
+                .and_then(|_| parts.iter().try_for_each(|part| self.render_part(part, &transforms)));
+            if result.is_err() {
+                break;
+            }
+        }
+        framebuffer.unbind(viewport);
+        result?;
+
+        Impostor::new(framebuffer.into_texture()?, self.view_count, half_size, center_height)
+    }
+
+    fn render_part(&self, part: &MeshPart, transforms: &[Matrix4<Float>]) -> Result<(), GraphicsError> {
+        let material = part.get_material();
+        let color = match material.get_uniform("diffuse_color") {
+            Some(UniformValue::Vec3(c)) => Vector3::new(c[0], c[1], c[2]),
+            _ => Vector3::new(1., 1., 1.)
+        };
+        self.shader.set_resource_vec3("diffuse_color", &color)?;
+        let texture = material.get_texture("diffuse_texture");
+        if let Some(texture) = texture {
+            texture.activate();
+        }
+        let result = part.get_mesh().render_instanced(transforms);
+        if let Some(texture) = texture {
+            texture.deactivate();
+        }
+        result
+    }
+}
+
+// half size of the square billboard, which covers the mesh from every direction
+// around the z axis, and the height of its center
+fn get_billboard_extent(bounds: &AABB) -> (Float, Float) {
+    let radius = bounds.get_corners().iter()
+        .map(|c| (c.x * c.x + c.y * c.y).sqrt())
+        .fold(0., Float::max);
+    let half_height = (bounds.get_max().z - bounds.get_min().z) / 2.;
+    (Float::max(radius, half_height).max(1e-3), bounds.get_min().z + half_height)
+}
+
+// orthographic view from the direction given by the angle around the z axis, looking at the origin
+fn create_view_projection_matrix(angle: Float, half_size: Float, center_height: Float) -> Matrix4<Float> {
+    let (sin, cos) = angle.sin_cos();
+    Matrix4::new(
+        Vector4::new(-sin / half_size, 0., -cos / half_size, 0.),
+        Vector4::new(cos / half_size, 0., -sin / half_size, 0.),
+        Vector4::new(0., 1. / half_size, 0., 0.),
+        Vector4::new(0., -center_height / half_size, 0., 1.)
+    )
+}
@@ -3,12 +3,26 @@ pub mod object_manager;
 pub mod object_prototype;
 pub mod object;
 pub mod object_error;
+pub mod spawn_rule;
+pub mod impostor;
+pub mod collision_shape;
+pub mod collider;
+pub mod impostor_baker;
 mod file_prototype;
 mod file_asset;
+mod file_material;
+mod prototype_loader;
 
 pub use self::object_manager::ObjectManager;
 pub use self::object::Object;
 pub use self::object_error::ObjectError;
-use self::object_prototype::ObjectPrototype;
+pub use self::spawn_rule::SpawnRule;
+pub use self::object_prototype::{ ObjectPrototype, PrototypeData, MeshPart };
+pub use self::impostor::Impostor;
+pub use self::collision_shape::CollisionShape;
+pub use self::collider::Collider;
+pub use self::impostor_baker::ImpostorBaker;
 use self::file_prototype::FilePrototype;
 use self::file_asset::FileAsset;
+use self::file_material::FileMaterial;
+use self::prototype_loader::PrototypeLoader;
@@ -1,11 +1,12 @@
 use std::sync::Arc;
-use glm::{ Vector3 };
+use glm::{ Vector3, Matrix4 };
 
-use crate::graphics::GraphicsError;
-use crate::{ Model, Float };
-use crate::traits::{ Rotatable, Translatable, Scalable, Renderable, RenderInfo };
-use super::ObjectPrototype;
+use crate::{ Model, Float, AABB, BoundingSphere };
+use crate::traits::{ Rotatable, Translatable, Scalable };
+use crate::scene::SceneNode;
+use super::{ ObjectPrototype, Collider };
 
+#[derive(Clone)]
 pub struct Object {
     prototype: Arc<ObjectPrototype>,
     model: Model
@@ -18,16 +19,31 @@ impl Object {
             model: Model::default()
         }
     }
-}
 
-impl Renderable for Object {
-	fn render<'a> (&self, info: &'a mut RenderInfo) -> Result<(), GraphicsError> {
-        let mvp = info.get_camera().create_mvp_matrix(&self.model);
-		let shader = info.get_active_shader();
-        shader.set_resource_mat4("mvp", &mvp)?;
-        shader.set_resource_mat4("model", self.model.get_matrix_ref())?;
-        self.prototype.render(info)
-	}
+    pub fn get_prototype(&self) -> &Arc<ObjectPrototype> {
+        &self.prototype
+    }
+
+    pub fn get_model_matrix(&self) -> &Matrix4<Float> {
+        self.model.get_matrix_ref()
+    }
+
+    // the translation including the parent of the object in the scene graph
+    pub fn get_world_translation(&self) -> Vector3<Float> {
+        self.model.get_world_translation()
+    }
+
+    pub fn get_bounds(&self) -> AABB {
+        self.prototype.get_bounds().transform(self.model.get_matrix_ref())
+    }
+
+    pub fn get_collider(&self) -> Option<Collider> {
+        self.prototype.get_collider(self.model.get_matrix_ref())
+    }
+
+    pub fn get_bounding_sphere(&self) -> BoundingSphere {
+        self.prototype.get_bounding_sphere().transform(self.model.get_matrix_ref())
+    }
 }
 
 impl Translatable for Object {
@@ -39,6 +55,12 @@ impl Translatable for Object {
     }
 }
 
+impl SceneNode for Object {
+    fn set_scene_transform(&mut self, local: &Model, parent_matrix: Option<&Matrix4<Float>>) {
+        self.model.set_scene_transform(local, parent_matrix);
+    }
+}
+
 impl Rotatable for Object {
     fn set_rotation(&mut self, new_rotation: Vector3<Float>) {
         self.model.set_rotation(new_rotation);
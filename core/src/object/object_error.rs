@@ -3,15 +3,18 @@ use std::error::Error;
 
 use serde_yaml;
 
-use crate::graphics::mesh::MeshError;
+use crate::graphics::{ GraphicsError, mesh::MeshError };
 use crate::file::FileError;
 
 #[derive(Debug)]
 pub enum ObjectError {
     Mesh(MeshError),
+    Graphics(GraphicsError),
     File(FileError),
     Yaml(serde_yaml::Error),
-    PrototypeNotExisting(String)
+    PrototypeNotExisting(String),
+    MissingMesh(String),
+    MaterialNotExisting(String)
 }
 
 impl From<MeshError> for ObjectError {
@@ -20,6 +23,12 @@ impl From<MeshError> for ObjectError {
     }
 }
 
+impl From<GraphicsError> for ObjectError {
+    fn from(err: GraphicsError) -> Self {
+        ObjectError::Graphics(err)
+    }
+}
+
 impl From<FileError> for ObjectError {
     fn from(err: FileError) -> Self {
         ObjectError::File(err)
@@ -38,18 +47,24 @@ impl Error for ObjectError {
     fn description(&self) -> &str {
         match *self {
             ObjectError::Mesh(_) => "mesh",
+            ObjectError::Graphics(_) => "graphics",
             ObjectError::File(_) => "file",
             ObjectError::Yaml(_) => "yaml",
             ObjectError::PrototypeNotExisting(_) => "protoype not existing",
+            ObjectError::MissingMesh(_) => "prototype without mesh",
+            ObjectError::MaterialNotExisting(_) => "material not existing",
         }
     }
 
     fn cause(&self) -> Option<&dyn Error> {
         match *self {
             ObjectError::Mesh(ref err) => Some(err),
+            ObjectError::Graphics(ref err) => Some(err),
             ObjectError::File(ref err) => Some(err),
             ObjectError::Yaml(ref err) => Some(err),
             ObjectError::PrototypeNotExisting(_) => None,
+            ObjectError::MissingMesh(_) => None,
+            ObjectError::MaterialNotExisting(_) => None,
         }
     }
 }
@@ -58,9 +73,12 @@ impl fmt::Display for ObjectError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ObjectError::Mesh(ref err) => write!(f, "{}/{}", self.description(), err),
+            ObjectError::Graphics(ref err) => write!(f, "{}/{}", self.description(), err),
             ObjectError::File(ref err) => write!(f, "{}/{}", self.description(), err),
             ObjectError::Yaml(ref err) => write!(f, "{}/{}", self.description(), err),
-            ObjectError::PrototypeNotExisting(ref name) => write!(f, "{}: '{}'", self.description(), name)
+            ObjectError::PrototypeNotExisting(ref name) => write!(f, "{}: '{}'", self.description(), name),
+            ObjectError::MissingMesh(ref name) => write!(f, "{}: '{}'", self.description(), name),
+            ObjectError::MaterialNotExisting(ref name) => write!(f, "{}: '{}'", self.description(), name)
         }
     }
 }
@@ -1,35 +1,131 @@
 use std::collections::BTreeMap;
-use std::sync::Arc;
+use std::sync::{ Arc, Mutex, MutexGuard, RwLock };
 
-use crate::file::read_file;
-use super::{ Object, ObjectPrototype, ObjectError, FilePrototype };
+use crate::graphics::Material;
+use crate::Float;
+use super::{ Object, ObjectPrototype, ObjectError, PrototypeLoader, SpawnRule, ImpostorBaker };
 
 pub struct ObjectManager {
-    prototype_map: BTreeMap<String, Arc<ObjectPrototype>>
+    prototype_map: BTreeMap<String, Arc<ObjectPrototype>>,
+    // replaced on reloads of the prototype file, while the chunk loader reads them
+    spawn_rules: RwLock<Vec<(String, SpawnRule)>>,
+    // only used on the render thread, the manager itself is shared with the chunk loader
+    loader: Mutex<PrototypeLoader>
 }
 
 impl ObjectManager {
 
-    pub fn from_yaml(file_path: &str) -> Result<ObjectManager, ObjectError> {
+    // the default distances switch to the second lod and to the impostor, if the file doesn't give them,
+    // with a baker, every prototype gets an impostor as its last lod
+    pub fn from_yaml(file_path: &str, default_distances: [Float; 2], baker: Option<&ImpostorBaker>) -> Result<ObjectManager, ObjectError> {
         info!("Creating object manager by yaml, path = '{}'", file_path);
-        let file = read_file(file_path)?;
-        let parsed_file: FilePrototype = serde_yaml::from_str(file.as_str())?;
+        let mut loader = PrototypeLoader::new(file_path, default_distances);
+        loader.read_assets()?;
 
         let mut obj_manager = ObjectManager::default();
-
-        for (name, lod0_path, lod1_path) in parsed_file.into_iter() {
-            info!("Loading prototype '{}', lod0 = '{}', lod1 = '{}'", name, lod0_path, lod1_path);
-            obj_manager.add_prototype(&name, &lod0_path, &lod1_path)?;
+        for asset in loader.get_assets().to_vec() {
+            let name = asset.get_name();
+            debug_assert!(!obj_manager.prototype_map.contains_key(name));
+            let data = loader.create_prototype_data(&asset, baker)?;
+            obj_manager.prototype_map.insert(name.to_string(), Arc::new(ObjectPrototype::new(name, data)));
+            if let Some(rule) = asset.get_spawn_rule() {
+                info!("Prototype '{}' gets spawned on the terrain", name);
+                obj_manager.add_spawn_rule(name, rule.clone());
+            }
         }
+        info!("Loaded {} prototypes with {} meshes and {} materials", obj_manager.prototype_map.len(), loader.get_mesh_count(), loader.get_materials().count());
+        obj_manager.loader = Mutex::new(loader);
 
         Ok(obj_manager)
     }
 
-    pub fn add_prototype(&mut self, name: &str, lod0_path: &str, lod1_path: &str) -> Result<(), ObjectError> {
-        debug_assert!(!self.prototype_map.contains_key(name));
-        let prototype = ObjectPrototype::from_obj(lod0_path, lod1_path)?;
-        self.prototype_map.insert(name.to_string(), Arc::new(prototype));
-        Ok(())
+    // prototypes created from one of the changed obj, mtl, material or texture files get rebuilt, a changed
+    // prototype file rebuilds all of them and replaces the spawn rules, prototypes, which fail to load,
+    // keep their previous meshes and materials.
+    // Added prototypes are only taken at startup.
+    pub fn reload(&self, changed_paths: &[String], baker: Option<&ImpostorBaker>) -> Vec<ObjectError> {
+        let mut loader = self.lock_loader();
+        let mut errors = Vec::new();
+        let file_changed = changed_paths.iter().any(|path| path == loader.get_file_path());
+        if file_changed {
+            info!("Reloading prototype file '{}'", loader.get_file_path());
+            match loader.read_assets() {
+                Ok(_) => self.replace_spawn_rules(loader.get_assets().iter()
+                    .filter(|asset| self.prototype_map.contains_key(asset.get_name()))
+                    .filter_map(|asset| asset.get_spawn_rule().map(|rule| (asset.get_name().to_string(), rule.clone())))
+                    .collect()),
+                Err(e) => errors.push(e)
+            }
+        }
+        loader.forget_files(changed_paths);
+
+        let mut reload_count = 0;
+        for asset in loader.get_assets().to_vec() {
+            if !file_changed && !loader.uses_any_file(asset.get_name(), changed_paths) {
+                continue;
+            }
+            let prototype = match self.prototype_map.get(asset.get_name()) {
+                Some(prototype) => prototype,
+                None => {
+                    warn!("Prototype '{}' is new, it needs a restart to be used", asset.get_name());
+                    continue;
+                }
+            };
+            match loader.create_prototype_data(&asset, baker) {
+                Ok(data) => {
+                    prototype.replace_data(data);
+                    reload_count += 1;
+                },
+                Err(e) => {
+                    warn!("Prototype '{}' keeps its previous meshes", asset.get_name());
+                    errors.push(e);
+                }
+            }
+        }
+        info!("Reloaded {} prototypes, errors = {}", reload_count, errors.len());
+        errors
+    }
+
+    pub fn get_file_path(&self) -> String {
+        self.lock_loader().get_file_path().to_string()
+    }
+
+    // the prototype file and all files the prototypes are created from
+    pub fn get_source_files(&self) -> Vec<String> {
+        let loader = self.lock_loader();
+        let mut files = vec![loader.get_file_path().to_string()];
+        files.extend(loader.get_used_files());
+        files
+    }
+
+    // every material has its own shader, the world sets the shared resources on all of them
+    pub fn get_materials(&self) -> Vec<Arc<Material>> {
+        self.lock_loader().get_materials().cloned().collect()
+    }
+
+    pub fn add_spawn_rule(&mut self, name: &str, rule: SpawnRule) {
+        debug_assert!(self.prototype_map.contains_key(name));
+        match self.spawn_rules.get_mut() {
+            Ok(spawn_rules) => spawn_rules.push((name.to_string(), rule)),
+            Err(poisoned) => poisoned.into_inner().push((name.to_string(), rule))
+        }
+    }
+
+    // the rules at the time of the call, later reloads don't change them
+    pub fn get_spawn_rules(&self) -> Vec<(String, SpawnRule)> {
+        // the lock is only held for swapping the rules, so a poisoned one still holds valid rules
+        match self.spawn_rules.read() {
+            Ok(spawn_rules) => spawn_rules.clone(),
+            Err(poisoned) => poisoned.into_inner().clone()
+        }
+    }
+
+    fn replace_spawn_rules(&self, spawn_rules: Vec<(String, SpawnRule)>) {
+        info!("Replacing spawn rules, {} prototypes get spawned", spawn_rules.len());
+        match self.spawn_rules.write() {
+            Ok(mut current) => *current = spawn_rules,
+            Err(poisoned) => *poisoned.into_inner() = spawn_rules
+        }
     }
 
     pub fn create_object(&self, prototype_name: &str) -> Result<Object, ObjectError> {
@@ -44,13 +140,22 @@ impl ObjectManager {
         }
     }
 
+    // the loader caches stay consistent, even if a reload panicked
+    fn lock_loader(&self) -> MutexGuard<'_, PrototypeLoader> {
+        match self.loader.lock() {
+            Ok(loader) => loader,
+            Err(poisoned) => poisoned.into_inner()
+        }
+    }
+
 }
 
 impl Default for ObjectManager {
     fn default() -> ObjectManager {
         ObjectManager {
-            prototype_map: BTreeMap::new()
+            prototype_map: BTreeMap::new(),
+            spawn_rules: RwLock::new(Vec::new()),
+            loader: Mutex::new(PrototypeLoader::new("", [0., 0.]))
         }
     }
 }
-
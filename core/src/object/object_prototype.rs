@@ -1,25 +1,213 @@
-use super::ObjectError;
-use crate::graphics::{GraphicsError, Mesh};
-use crate::traits::{RenderInfo, Renderable};
+use std::sync::{Arc, RwLock};
 
+use glm::Matrix4;
+
+use super::{Collider, CollisionShape, Impostor, ImpostorBaker, ObjectError};
+use crate::graphics::{GraphicsError, Material, Mesh};
+use crate::traits::RenderInfo;
+use crate::{BoundingSphere, Float, AABB};
+
+// objects share their prototype, so its data gets replaced as a whole on reloads,
+// while the objects keep using the same prototype
 pub struct ObjectPrototype {
-    lod_meshes: [Mesh; 2],
+    name: String,
+    data: RwLock<Arc<PrototypeData>>,
+}
+
+// the meshes of a prototype with everything derived from them
+pub struct PrototypeData {
+    lods: Vec<ObjectLod>,
+    impostor: Option<Impostor>,
+    impostor_distance: Float,
+    collision_shape: CollisionShape,
+    // bounds of the most detailed lod, merged once as they are needed for every object and frame
+    bounds: AABB,
+    bounding_sphere: BoundingSphere,
+}
+
+// meshes and materials can be shared between lods and prototypes
+struct ObjectLod {
+    parts: Vec<MeshPart>,
+    distance: Float,
+}
+
+// the faces of a lod using one material
+pub struct MeshPart {
+    mesh: Arc<Mesh>,
+    material: Arc<Material>,
+}
+
+impl MeshPart {
+    pub fn new(mesh: Arc<Mesh>, material: Arc<Material>) -> MeshPart {
+        MeshPart {
+            mesh: mesh,
+            material: material,
+        }
+    }
+
+    pub fn get_mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    pub fn get_material(&self) -> &Arc<Material> {
+        &self.material
+    }
 }
 
 impl ObjectPrototype {
-    pub fn from_obj(lod0_path: &str, lod1_path: &str) -> Result<ObjectPrototype, ObjectError> {
-        let lod0 = Mesh::from_obj(lod0_path)?;
-        let lod1 = Mesh::from_obj(lod1_path)?;
-        let proto = ObjectPrototype {
-            lod_meshes: [lod0, lod1],
-        };
-        Ok(proto)
+    pub fn new(name: &str, data: PrototypeData) -> ObjectPrototype {
+        ObjectPrototype {
+            name: name.to_string(),
+            data: RwLock::new(Arc::new(data)),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // the data stays valid while it is held, even if the prototype gets reloaded meanwhile
+    pub fn get_data(&self) -> Arc<PrototypeData> {
+        // the lock is only held for swapping the data, so a poisoned one still holds valid data
+        match self.data.read() {
+            Ok(data) => data.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    // all objects of the prototype use the new data from now on
+    pub fn replace_data(&self, data: PrototypeData) {
+        match self.data.write() {
+            Ok(mut current) => *current = Arc::new(data),
+            Err(poisoned) => *poisoned.into_inner() = Arc::new(data),
+        }
+    }
+
+    // collider of an instance with the model matrix, none for prototypes without collision
+    pub fn get_collider(&self, model_matrix: &Matrix4<Float>) -> Option<Collider> {
+        self.get_data().get_collider(model_matrix)
+    }
+
+    pub fn get_bounds(&self) -> AABB {
+        self.get_data().get_bounds()
+    }
+
+    pub fn get_bounding_sphere(&self) -> BoundingSphere {
+        self.get_data().get_bounding_sphere()
     }
 }
 
-impl Renderable for ObjectPrototype {
-    fn render<'a>(&self, info: &'a mut RenderInfo) -> Result<(), GraphicsError> {
-        debug_assert!(info.get_lod() < 2);
-        self.lod_meshes[info.get_lod() as usize].render(info)
+impl PrototypeData {
+    // lods are given from the most detailed on with the camera distance, from which on they are used,
+    // the meshes need instancing enabled
+    pub fn new(
+        name: &str,
+        lods: Vec<(Vec<MeshPart>, Float)>,
+    ) -> Result<PrototypeData, ObjectError> {
+        if lods.is_empty() || lods.iter().any(|(parts, _)| parts.is_empty()) {
+            return Err(ObjectError::MissingMesh(name.to_string()));
+        }
+        let lods: Vec<ObjectLod> = lods
+            .into_iter()
+            .map(|(parts, distance)| ObjectLod {
+                parts: parts,
+                distance: distance,
+            })
+            .collect();
+        let bounds = merge_bounds(&lods[0].parts);
+        let bounding_sphere = merge_bounding_spheres(&lods[0].parts);
+        let data = PrototypeData {
+            lods: lods,
+            impostor: None,
+            impostor_distance: Float::INFINITY,
+            collision_shape: CollisionShape::None,
+            bounds: bounds,
+            bounding_sphere: bounding_sphere,
+        };
+        Ok(data)
+    }
+
+    // the impostor is used from the distance on, after all meshes
+    pub fn bake_impostor(
+        &mut self,
+        baker: &ImpostorBaker,
+        distance: Float,
+    ) -> Result<(), ObjectError> {
+        self.impostor = Some(baker.bake(&self.lods[0].parts)?);
+        self.impostor_distance = distance;
+        Ok(())
+    }
+
+    pub fn set_collision_shape(&mut self, collision_shape: CollisionShape) {
+        self.collision_shape = collision_shape;
+    }
+
+    pub fn get_collider(&self, model_matrix: &Matrix4<Float>) -> Option<Collider> {
+        self.collision_shape.to_collider(&self.bounds, model_matrix)
     }
+
+    pub fn has_impostor(&self) -> bool {
+        self.impostor.is_some()
+    }
+
+    pub fn get_lod_count(&self) -> usize {
+        self.lods.len()
+    }
+
+    // the impostor comes after the meshes
+    pub fn is_impostor_lod(&self, lod: u8) -> bool {
+        self.has_impostor() && lod as usize == self.lods.len()
+    }
+
+    pub fn select_lod(&self, distance: Float) -> u8 {
+        if self.has_impostor() && distance >= self.impostor_distance {
+            return self.lods.len() as u8;
+        }
+        self.lods
+            .iter()
+            .rposition(|lod| distance >= lod.distance)
+            .unwrap_or(0) as u8
+    }
+
+    pub fn get_bounds(&self) -> AABB {
+        self.bounds
+    }
+
+    pub fn get_bounding_sphere(&self) -> BoundingSphere {
+        self.bounding_sphere
+    }
+
+    // every part needs its material bound, so parts of several prototypes are
+    // drawn together by their material, lods beyond the meshes use the last one
+    pub fn get_parts(&self, lod: u8) -> &[MeshPart] {
+        &self.lods[usize::min(lod as usize, self.lods.len() - 1)].parts
+    }
+
+    // one draw call for all instances, the model matrices are passed as vertex attributes,
+    // the impostor shader must be active
+    pub fn render_impostor_instanced(
+        &self,
+        info: &mut RenderInfo,
+        transforms: &[Matrix4<Float>],
+    ) -> Result<(), GraphicsError> {
+        match self.impostor {
+            Some(ref impostor) => impostor.render_instanced(info, transforms),
+            None => Ok(()),
+        }
+    }
+}
+
+// parts must not be empty
+fn merge_bounds(parts: &[MeshPart]) -> AABB {
+    let mut parts = parts.iter();
+    let first = parts.next().unwrap().mesh.get_bounds();
+    parts.fold(first, |bounds, part| bounds.merge(&part.mesh.get_bounds()))
+}
+
+fn merge_bounding_spheres(parts: &[MeshPart]) -> BoundingSphere {
+    let mut parts = parts.iter();
+    let first = parts.next().unwrap().mesh.get_bounding_sphere();
+    parts.fold(first, |sphere, part| {
+        sphere.merge(&part.mesh.get_bounding_sphere())
+    })
 }
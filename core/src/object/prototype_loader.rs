@@ -0,0 +1,234 @@
+use std::collections::{ BTreeMap, BTreeSet };
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::file::read_file;
+use crate::graphics::{ Mesh, Material, mesh::{ read_mtl, MtlMaterial }};
+use crate::Float;
+use super::{ PrototypeData, ObjectError, FilePrototype, FileAsset, FileMaterial, MeshPart, ImpostorBaker };
+
+// used for faces without a material, also the base of materials from mtl files
+const DEFAULT_MATERIAL: &str = "default";
+
+// creates the prototype data of the assets in the prototype file, the meshes and materials
+// are kept, so they are shared between prototypes and reloads only read the changed files
+pub struct PrototypeLoader {
+    file_path: String,
+    default_distances: [Float; 2],
+    assets: Vec<FileAsset>,
+    // meshes of every material used in an obj file by its path, so every file is loaded only once
+    mesh_map: BTreeMap<String, ObjMeshes>,
+    // materials by the mtl file they are taken from, none for material files,
+    // with the files they are read from
+    material_map: BTreeMap<(Option<String>, String), (Arc<Material>, Vec<String>)>,
+    // materials of the mtl files by their file and name, used for names without material file
+    mtl_map: BTreeMap<(String, String), MtlMaterial>,
+    mtl_files: BTreeSet<String>,
+    // every file a prototype was created from by the prototype name
+    prototype_files: BTreeMap<String, BTreeSet<String>>,
+    material_dir: String
+}
+
+#[derive(Clone)]
+struct ObjMeshes {
+    // the mtl file named by the obj file, its materials are only used by this file
+    material_lib: Option<String>,
+    meshes: Vec<(Option<String>, Arc<Mesh>)>
+}
+
+impl PrototypeLoader {
+    // the default distances switch to the second lod and to the impostor, if the file doesn't give them
+    pub fn new(file_path: &str, default_distances: [Float; 2]) -> PrototypeLoader {
+        PrototypeLoader {
+            file_path: file_path.to_string(),
+            default_distances: default_distances,
+            assets: Vec::new(),
+            mesh_map: BTreeMap::new(),
+            material_map: BTreeMap::new(),
+            mtl_map: BTreeMap::new(),
+            mtl_files: BTreeSet::new(),
+            prototype_files: BTreeMap::new(),
+            material_dir: String::new()
+        }
+    }
+
+    pub fn get_file_path(&self) -> &str {
+        &self.file_path
+    }
+
+    pub fn get_assets(&self) -> &[FileAsset] {
+        &self.assets
+    }
+
+    // the previous assets are kept, if the file can't be read
+    pub fn read_assets(&mut self) -> Result<(), ObjectError> {
+        let file = read_file(&self.file_path)?;
+        let mut parsed_file: FilePrototype = serde_yaml::from_str(file.as_str())?;
+        parsed_file.prepare_root_dir();
+        self.material_dir = parsed_file.get_material_dir().to_string();
+        self.assets = parsed_file.into_iter().collect();
+        Ok(())
+    }
+
+    // obj, mtl, material and texture files get read again on their next use,
+    // prototypes still using the old meshes and materials keep them
+    pub fn forget_files(&mut self, paths: &[String]) {
+        for path in paths {
+            if self.mesh_map.remove(path).is_some() {
+                debug!("Mesh file '{}' gets reloaded on its next use", path);
+            }
+            if self.mtl_files.remove(path) {
+                debug!("Mtl file '{}' gets reloaded on its next use", path);
+                self.mtl_map.retain(|(mtl_path, _), _| mtl_path != path);
+            }
+        }
+        self.material_map.retain(|(_, name), (_, files)| {
+            let changed = files.iter().any(|file| paths.contains(file));
+            if changed {
+                debug!("Material '{}' gets reloaded on its next use", name);
+            }
+            !changed
+        });
+    }
+
+    // true, if the prototype was created from one of the files
+    pub fn uses_any_file(&self, prototype_name: &str, paths: &[String]) -> bool {
+        match self.prototype_files.get(prototype_name) {
+            Some(files) => paths.iter().any(|path| files.contains(path)),
+            None => false
+        }
+    }
+
+    // the files all prototypes were created from, without the prototype file
+    pub fn get_used_files(&self) -> BTreeSet<String> {
+        self.prototype_files.values().flatten().cloned().collect()
+    }
+
+    pub fn get_mesh_count(&self) -> usize {
+        self.mesh_map.len()
+    }
+
+    pub fn get_materials(&self) -> impl Iterator<Item = &Arc<Material>> {
+        self.material_map.values().map(|(material, _)| material)
+    }
+
+    // with a baker, the prototype gets an impostor as its last lod
+    pub fn create_prototype_data(&mut self, asset: &FileAsset, baker: Option<&ImpostorBaker>) -> Result<PrototypeData, ObjectError> {
+        let name = asset.get_name();
+        let mut lods: Vec<(Vec<MeshPart>, Float)> = Vec::new();
+        let mut files = BTreeSet::new();
+        let mut previous_path = "";
+        for (index, file_lod) in asset.get_lods().iter().enumerate() {
+            let path = file_lod.get_mesh_path();
+            // a lod with the same mesh as the previous one changes nothing
+            if path == previous_path {
+                continue;
+            }
+            let distance = match index {
+                0 => 0.,
+                _ => file_lod.get_distance().unwrap_or(self.default_distances[0] * index as Float)
+            };
+            let distance = lods.last().map_or(distance, |(_, previous)| Float::max(distance, *previous));
+            info!("Prototype '{}' uses '{}' from distance {}", name, path, distance);
+            let mut parts = Vec::new();
+            let obj_meshes = self.load_meshes(path)?;
+            files.insert(path.to_string());
+            for (material, mesh) in obj_meshes.meshes {
+                let material = material.as_ref().map(|m| m.as_str())
+                    .or(asset.get_material())
+                    .unwrap_or(DEFAULT_MATERIAL);
+                let (material, material_files) = self.get_material(material, obj_meshes.material_lib.as_ref().map(|lib| lib.as_str()))?;
+                files.extend(material_files);
+                parts.push(MeshPart::new(mesh, material));
+            }
+            lods.push((parts, distance));
+            previous_path = path;
+        }
+        let mut data = PrototypeData::new(name, lods)?;
+        data.set_collision_shape(asset.get_collision_shape());
+        if let Some(baker) = baker {
+            let distance = asset.get_impostor_distance().unwrap_or(self.default_distances[1]);
+            info!("Prototype '{}' uses its impostor from distance {}", name, distance);
+            data.bake_impostor(baker, distance)?;
+        }
+        self.prototype_files.insert(name.to_string(), files);
+        Ok(data)
+    }
+
+    fn load_meshes(&mut self, path: &str) -> Result<ObjMeshes, ObjectError> {
+        if let Some(obj_meshes) = self.mesh_map.get(path) {
+            return Ok(obj_meshes.clone());
+        }
+        let (material_lib, meshes) = Mesh::from_obj_by_material(path)?;
+        let mut loaded_meshes = Vec::new();
+        for (material, mut mesh) in meshes {
+            mesh.enable_instancing()?;
+            loaded_meshes.push((material, Arc::new(mesh)));
+        }
+        let obj_meshes = ObjMeshes {
+            material_lib: material_lib,
+            meshes: loaded_meshes
+        };
+        self.mesh_map.insert(path.to_string(), obj_meshes.clone());
+        Ok(obj_meshes)
+    }
+
+    // mtl files are read on their first use
+    fn load_mtl(&mut self, path: &str) -> Result<(), ObjectError> {
+        if self.mtl_files.contains(path) {
+            return Ok(());
+        }
+        for mtl in read_mtl(path)? {
+            self.mtl_map.insert((path.to_string(), mtl.get_name().to_string()), mtl);
+        }
+        self.mtl_files.insert(path.to_string());
+        Ok(())
+    }
+
+    // material files take precedence over the materials of mtl files,
+    // those are only looked up in the mtl file of the obj file.
+    // Returns the material with the files it is read from
+    fn get_material(&mut self, name: &str, material_lib: Option<&str>) -> Result<(Arc<Material>, Vec<String>), ObjectError> {
+        let file_key = (None, name.to_string());
+        let mtl_key = (material_lib.map(|lib| lib.to_string()), name.to_string());
+        if let Some(material) = self.material_map.get(&file_key).or_else(|| self.material_map.get(&mtl_key)) {
+            return Ok(material.clone());
+        }
+        if let Some(lib) = material_lib {
+            self.load_mtl(lib)?;
+        }
+        let (key, file_material, mut files) = match self.read_material_file(name)? {
+            Some(file_material) => (file_key, file_material, vec![self.get_material_path(name)]),
+            None => match material_lib.and_then(|lib| self.mtl_map.get(&(lib.to_string(), name.to_string())).map(|mtl| (lib, mtl))) {
+                Some((lib, mtl)) => {
+                    info!("Material '{}' is taken from mtl file '{}'", name, lib);
+                    match self.read_material_file(DEFAULT_MATERIAL)? {
+                        Some(default_material) => {
+                            let files = vec![lib.to_string(), self.get_material_path(DEFAULT_MATERIAL)];
+                            (mtl_key, default_material.with_mtl(mtl), files)
+                        },
+                        None => return Err(ObjectError::MaterialNotExisting(DEFAULT_MATERIAL.to_string()))
+                    }
+                },
+                None => return Err(ObjectError::MaterialNotExisting(name.to_string()))
+            }
+        };
+        files.extend(file_material.get_texture_paths().cloned());
+        let material = Arc::new(file_material.create_material(name)?);
+        self.material_map.insert(key, (material.clone(), files.clone()));
+        Ok((material, files))
+    }
+
+    fn get_material_path(&self, name: &str) -> String {
+        self.material_dir.clone() + name + ".yaml"
+    }
+
+    fn read_material_file(&self, name: &str) -> Result<Option<FileMaterial>, ObjectError> {
+        let path = self.get_material_path(name);
+        if !Path::new(&path).is_file() {
+            return Ok(None);
+        }
+        let file = read_file(&path)?;
+        Ok(Some(serde_yaml::from_str(file.as_str())?))
+    }
+}
@@ -0,0 +1,109 @@
+use serde::Deserialize;
+
+use crate::Float;
+
+// declares where and how objects of a prototype get placed on the terrain,
+// ranges are given as [min, max], missing entries allow everything
+#[derive(Deserialize, Clone)]
+pub struct SpawnRule {
+    // terrain type names as in the surface texture file, empty for any terrain
+    #[serde(default)]
+    terrain: Vec<String>,
+    #[serde(default = "default_altitude")]
+    altitude: [f64; 2],
+    // in degrees from the horizontal
+    #[serde(default = "default_slope")]
+    slope: [f64; 2],
+    // probability to keep a scattered position, between 0 and 1
+    #[serde(default = "default_density")]
+    density: f64,
+    // scales the density by the vegetation of the terrain, forming forests and clearings
+    #[serde(default)]
+    vegetation: bool,
+    // minimum distance between objects of this prototype
+    #[serde(default = "default_min_distance")]
+    min_distance: f64,
+    #[serde(default = "default_scale")]
+    scale: [Float; 2],
+    // additional vertical scale
+    #[serde(default = "default_scale")]
+    height_scale: [Float; 2],
+    // around the up axis, in radians
+    #[serde(default = "default_rotation")]
+    rotation: [Float; 2],
+    // around the horizontal axes, in radians
+    #[serde(default)]
+    tilt: [Float; 2],
+    #[serde(default)]
+    align_to_normal: bool
+}
+
+impl SpawnRule {
+    pub fn get_terrain_names(&self) -> &[String] {
+        &self.terrain
+    }
+
+    pub fn get_altitude_range(&self) -> [f64; 2] {
+        self.altitude
+    }
+
+    pub fn get_slope_range(&self) -> [f64; 2] {
+        self.slope
+    }
+
+    pub fn get_density(&self) -> f64 {
+        self.density
+    }
+
+    pub fn follows_vegetation(&self) -> bool {
+        self.vegetation
+    }
+
+    pub fn get_min_distance(&self) -> f64 {
+        self.min_distance
+    }
+
+    pub fn get_scale_range(&self) -> [Float; 2] {
+        self.scale
+    }
+
+    pub fn get_height_scale_range(&self) -> [Float; 2] {
+        self.height_scale
+    }
+
+    pub fn get_rotation_range(&self) -> [Float; 2] {
+        self.rotation
+    }
+
+    pub fn get_tilt_range(&self) -> [Float; 2] {
+        self.tilt
+    }
+
+    pub fn is_aligned_to_normal(&self) -> bool {
+        self.align_to_normal
+    }
+}
+
+fn default_altitude() -> [f64; 2] {
+    [std::f64::MIN, std::f64::MAX]
+}
+
+fn default_slope() -> [f64; 2] {
+    [0., 90.]
+}
+
+fn default_density() -> f64 {
+    1.
+}
+
+fn default_min_distance() -> f64 {
+    8.
+}
+
+fn default_scale() -> [Float; 2] {
+    [1., 1.]
+}
+
+fn default_rotation() -> [Float; 2] {
+    [0., 2. * std::f32::consts::PI as Float]
+}
@@ -2,7 +2,7 @@ use std::ops::{ Add, Sub };
 
 use glm:: { Vector3, GenNum, normalize, cross, length };
 
-use crate::{ Float, Model, Camera, UpdateError, graphics::create_direction};
+use crate::{ Float, Model, UpdateError, graphics::create_direction};
 use crate::traits::{ Translatable, Rotatable, Updatable };
 
 pub struct Player {
@@ -14,13 +14,6 @@ pub struct Player {
 }
 
 impl Player {
-    pub fn align_camera(&self, camera: &mut Camera) {
-        let mut pos = self.get_translation();
-        pos.z += 3.;
-        camera.set_translation(pos);
-        camera.set_rotation(self.get_rotation());
-    }
-
     pub fn is_jumping(&self) -> bool {
         self.jumping
     }
@@ -104,6 +97,10 @@ impl Player {
         self.momentum = self.momentum.add(additional_momentum);
     }
 
+    pub fn get_momentum(&self) -> Vector3<Float> {
+        self.momentum
+    }
+
     pub fn push_z(&mut self, additional_momentum_z: Float) {
         self.momentum.z += additional_momentum_z;
     }
@@ -0,0 +1,9 @@
+pub mod scene_graph;
+pub mod scene_node;
+pub mod scene_error;
+pub mod node_id;
+
+pub use self::scene_graph::SceneGraph;
+pub use self::scene_node::SceneNode;
+pub use self::scene_error::SceneError;
+pub use self::node_id::NodeId;
@@ -0,0 +1,26 @@
+use std::fmt;
+
+// identifies a node by its slot in the scene graph, slots are never reused,
+// so the id stays valid until the node is removed
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId {
+    slot: usize
+}
+
+impl NodeId {
+    pub fn new(slot: usize) -> Self {
+        Self {
+            slot: slot
+        }
+    }
+
+    pub fn get_slot(&self) -> usize {
+        self.slot
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "node #{}", self.slot)
+    }
+}
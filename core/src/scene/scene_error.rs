@@ -0,0 +1,36 @@
+use std::fmt;
+use std::error::Error;
+
+use super::NodeId;
+
+#[derive(Debug)]
+pub enum SceneError {
+    NodeNotExisting(NodeId),
+    Cycle(NodeId, NodeId)
+}
+
+impl Error for SceneError {
+
+    fn description(&self) -> &str {
+        match *self {
+            SceneError::NodeNotExisting(_) => "node not existing",
+            SceneError::Cycle(_, _) => "node would become its own ancestor"
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            SceneError::NodeNotExisting(_) => None,
+            SceneError::Cycle(_, _) => None
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SceneError::NodeNotExisting(id) => write!(f, "{}: {}", self.description(), id),
+            SceneError::Cycle(child, parent) => write!(f, "{}: attaching {} to {}", self.description(), child, parent)
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::cell::Cell;
+
+use glm::Matrix4;
+use num_traits::One;
+
+use crate::{ Float, Model };
+use super::{ NodeId, SceneNode, SceneError };
+
+// hierarchy of local transforms, the world matrices are only calculated on request
+// and cached until the transform of the node or one of its ancestors changes
+pub struct SceneGraph {
+    nodes: Vec<Option<Node>>
+}
+
+struct Node {
+    local: Model,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    world_matrix: Cell<Matrix4<Float>>,
+    dirty: Cell<bool>
+}
+
+impl SceneGraph {
+    pub fn add_node(&mut self, local: Model) -> NodeId {
+        let id = NodeId::new(self.nodes.len());
+        self.nodes.push(Some(Node {
+            local: local,
+            parent: None,
+            children: Vec::new(),
+            world_matrix: Cell::new(Matrix4::one()),
+            dirty: Cell::new(true)
+        }));
+        id
+    }
+
+    pub fn add_child(&mut self, parent: NodeId, local: Model) -> Result<NodeId, SceneError> {
+        self.get_node(parent)?;
+        let id = self.add_node(local);
+        self.attach(id, parent)?;
+        Ok(id)
+    }
+
+    // the children become root nodes, keeping their local transforms
+    pub fn remove_node(&mut self, id: NodeId) -> Result<Model, SceneError> {
+        self.detach(id)?;
+        let node = self.nodes[id.get_slot()].take().unwrap();
+        for child in node.children.iter() {
+            if let Some(Some(child_node)) = self.nodes.get_mut(child.get_slot()) {
+                child_node.parent = None;
+            }
+            self.mark_dirty(*child);
+        }
+        Ok(node.local)
+    }
+
+    // the local transform of the child becomes relative to the parent,
+    // a node attached to another parent gets detached from it before
+    pub fn attach(&mut self, child: NodeId, parent: NodeId) -> Result<(), SceneError> {
+        self.get_node(parent)?;
+        if self.is_ancestor(child, parent)? {
+            return Err(SceneError::Cycle(child, parent));
+        }
+        self.detach(child)?;
+        self.get_node_mut(child)?.parent = Some(parent);
+        self.get_node_mut(parent)?.children.push(child);
+        self.mark_dirty(child);
+        Ok(())
+    }
+
+    // the node becomes a root node, keeping its local transform
+    pub fn detach(&mut self, child: NodeId) -> Result<(), SceneError> {
+        let parent = match self.get_node(child)?.parent {
+            Some(parent) => parent,
+            None => return Ok(())
+        };
+        self.get_node_mut(parent)?.children.retain(|c| *c != child);
+        self.get_node_mut(child)?.parent = None;
+        self.mark_dirty(child);
+        Ok(())
+    }
+
+    pub fn get_parent(&self, id: NodeId) -> Result<Option<NodeId>, SceneError> {
+        Ok(self.get_node(id)?.parent)
+    }
+
+    pub fn get_children(&self, id: NodeId) -> Result<&[NodeId], SceneError> {
+        Ok(&self.get_node(id)?.children)
+    }
+
+    pub fn get_local(&self, id: NodeId) -> Result<&Model, SceneError> {
+        Ok(&self.get_node(id)?.local)
+    }
+
+    // the world matrices of the node and its descendants get recalculated on their next request
+    pub fn get_local_mut(&mut self, id: NodeId) -> Result<&mut Model, SceneError> {
+        self.get_node(id)?;
+        self.mark_dirty(id);
+        Ok(&mut self.get_node_mut(id)?.local)
+    }
+
+    pub fn get_world_matrix(&self, id: NodeId) -> Result<Matrix4<Float>, SceneError> {
+        let node = self.get_node(id)?;
+        if node.dirty.get() {
+            let local = *node.local.get_matrix_ref();
+            let world_matrix = match node.parent {
+                Some(parent) => self.get_world_matrix(parent)? * local,
+                None => local
+            };
+            node.world_matrix.set(world_matrix);
+            node.dirty.set(false);
+        }
+        Ok(node.world_matrix.get())
+    }
+
+    // passes the transform of the node to something placed by it, e.g. an object or the camera
+    pub fn apply<N: SceneNode>(&self, id: NodeId, scene_node: &mut N) -> Result<(), SceneError> {
+        let node = self.get_node(id)?;
+        match node.parent {
+            Some(parent) => {
+                let parent_matrix = self.get_world_matrix(parent)?;
+                scene_node.set_scene_transform(&node.local, Some(&parent_matrix));
+            },
+            None => scene_node.set_scene_transform(&node.local, None)
+        }
+        Ok(())
+    }
+
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.iter().filter(|node| node.is_some()).count()
+    }
+
+    // true, if the node is the other one or one of its ancestors
+    fn is_ancestor(&self, id: NodeId, other: NodeId) -> Result<bool, SceneError> {
+        let mut current = Some(other);
+        while let Some(node) = current {
+            if node == id {
+                return Ok(true);
+            }
+            current = self.get_node(node)?.parent;
+        }
+        Ok(false)
+    }
+
+    // descendants, which are already dirty, have their descendants dirty too
+    fn mark_dirty(&self, id: NodeId) {
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if let Ok(node) = self.get_node(current) {
+                if current != id && node.dirty.get() {
+                    continue;
+                }
+                node.dirty.set(true);
+                stack.extend(node.children.iter());
+            }
+        }
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<&Node, SceneError> {
+        match self.nodes.get(id.get_slot()) {
+            Some(Some(node)) => Ok(node),
+            _ => Err(SceneError::NodeNotExisting(id))
+        }
+    }
+
+    fn get_node_mut(&mut self, id: NodeId) -> Result<&mut Node, SceneError> {
+        match self.nodes.get_mut(id.get_slot()) {
+            Some(Some(node)) => Ok(node),
+            _ => Err(SceneError::NodeNotExisting(id))
+        }
+    }
+}
+
+impl Default for SceneGraph {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new()
+        }
+    }
+}
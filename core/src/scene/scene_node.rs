@@ -0,0 +1,16 @@
+use glm::Matrix4;
+
+use crate::{ Float, Model };
+
+// something, which gets its transform from a node of the scene graph
+pub trait SceneNode {
+    // the local transform of the node and the world matrix of its parent, none for root nodes
+    fn set_scene_transform(&mut self, local: &Model, parent_matrix: Option<&Matrix4<Float>>);
+}
+
+impl SceneNode for Model {
+    fn set_scene_transform(&mut self, local: &Model, parent_matrix: Option<&Matrix4<Float>>) {
+        *self = local.clone();
+        self.set_parent_matrix(parent_matrix);
+    }
+}
@@ -1,10 +1,11 @@
 use std::rc::Rc;
-use glm::{ Vector3, GenNum };
+use glm::{ Vector3, Matrix4, GenNum };
 
 use crate::graphics::{ Mesh, Model, ShaderProgram, ShaderProgramBuilder, Texture, TextureBuilder, GraphicsError, texture::Orientation };
 use crate::graphics::mesh::vertex_buffer::{ BUFFER_POSTION };
 use crate::{ Float, CoreError};
 use crate::traits::{ Translatable, Scalable, Renderable, RenderInfo };
+use crate::scene::SceneNode;
 
 pub struct Skybox {
     texture: Texture,
@@ -85,6 +86,12 @@ impl Renderable for Skybox {
 	}
 }
 
+impl SceneNode for Skybox {
+    fn set_scene_transform(&mut self, local: &Model, parent_matrix: Option<&Matrix4<Float>>) {
+        self.model.set_scene_transform(local, parent_matrix);
+    }
+}
+
 impl Translatable for Skybox {
     fn set_translation(&mut self, mut new_translation: Vector3<Float>) {
         new_translation[2] = self.origin_z;
@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use gl::types::GLuint;
+use glm::{ Vector3, cross, normalize };
+
+use core::Float;
+use core::graphics::mesh::VertexBuffer;
+use crate::ChunkPos;
+use super::{ HeightMap, Architect };
+
+// right-triangulated irregular network, triangles are split along their hypotenuse,
+// as long as the height error at its midpoint exceeds the maximum error of the lod.
+// The chunk borders always keep the full resolution, so neighbouring chunks don't crack,
+// and triangles covering cells of different terrain get split, so every triangle has one texture layer.
+pub struct AdaptiveMesher {
+    flat_shading: bool,
+    max_errors: [f64; 3],
+    triangle_coords: BTreeMap<i32, Arc<Vec<[i32; 4]>>>
+}
+
+impl AdaptiveMesher {
+    pub fn new(flat_shading: bool, max_errors: [f64; 3], grid_sizes: &[i32]) -> Self {
+        let triangle_coords = grid_sizes.iter()
+            .map(|size| (*size, Arc::new(create_triangle_coords(*size))))
+            .collect();
+        Self {
+            flat_shading: flat_shading,
+            max_errors: max_errors,
+            triangle_coords: triangle_coords
+        }
+    }
+
+    pub fn create_buffer(&self, origin: ChunkPos, lod: u8, architect: &Architect, height_map: &HeightMap) -> VertexBuffer {
+        let size = height_map.get_size();
+        debug_assert!(((size - 1) & (size - 2)) == 0, "Adaptive meshing needs a grid size of 2^n + 1");
+        let coords = match self.triangle_coords.get(&size) {
+            Some(coords) => coords.clone(),
+            None => Arc::new(create_triangle_coords(size))
+        };
+        let cell_layers = get_cell_layers(origin, architect, height_map);
+        let errors = calculate_errors(height_map, &coords, &cell_layers);
+        let max_error = self.max_errors[usize::min(lod as usize, self.max_errors.len() - 1)];
+
+        let mut triangles: Vec<[[i32; 2]; 3]> = Vec::new();
+        let max = size - 1;
+        collect_triangles(&errors, size, max_error, [[0, 0], [max, max], [max, 0]], &mut triangles);
+        collect_triangles(&errors, size, max_error, [[max, max], [0, 0], [0, max]], &mut triangles);
+
+        let mut builder = BufferBuilder::new(height_map);
+        if self.flat_shading {
+            for triangle in triangles.iter() {
+                let layer = get_triangle_layer(&cell_layers, size, triangle);
+                let corners = [triangle[0], triangle[2], triangle[1]];
+                let positions: Vec<Vector3<Float>> = corners.iter().map(|c| builder.get_position(*c)).collect();
+                let normal = normalize(cross(positions[1] - positions[0], positions[2] - positions[0]));
+                for corner in corners.iter() {
+                    builder.add_vertex(*corner, normal, layer);
+                }
+            }
+        } else {
+            let normals = architect.create_normals(origin, height_map);
+            // vertices are only shared by triangles of the same layer, so the layer isn't interpolated
+            let mut vertex_indices: BTreeMap<(usize, u32), GLuint> = BTreeMap::new();
+            for triangle in triangles.iter() {
+                let layer = get_triangle_layer(&cell_layers, size, triangle);
+                // reordering, so that the triangles face upwards
+                for corner in [triangle[0], triangle[2], triangle[1]].iter() {
+                    let grid_index = (corner[0] + corner[1] * size) as usize;
+                    match vertex_indices.get(&(grid_index, layer)) {
+                        Some(index) => builder.add_index(*index),
+                        None => {
+                            let index = builder.add_vertex(*corner, normals[grid_index], layer);
+                            vertex_indices.insert((grid_index, layer), index);
+                        }
+                    }
+                }
+            }
+        }
+        builder.finish()
+    }
+}
+
+// layer of the terrain covered by a triangle, triangles of several layers get split
+#[derive(Clone, Copy, PartialEq)]
+enum CoveredLayer {
+    Unknown,
+    Single(u32),
+    Mixed
+}
+
+impl CoveredLayer {
+    fn merge(self, other: CoveredLayer) -> CoveredLayer {
+        match (self, other) {
+            (CoveredLayer::Unknown, layer) | (layer, CoveredLayer::Unknown) => layer,
+            (CoveredLayer::Single(a), CoveredLayer::Single(b)) if a == b => self,
+            _ => CoveredLayer::Mixed
+        }
+    }
+}
+
+struct BufferBuilder<'a> {
+    height_map: &'a HeightMap,
+    positions: Vec<Float>,
+    uvs: Vec<Float>,
+    normals: Vec<Float>,
+    indices: Vec<GLuint>
+}
+
+impl<'a> BufferBuilder<'a> {
+    fn new(height_map: &'a HeightMap) -> Self {
+        Self {
+            height_map: height_map,
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new()
+        }
+    }
+
+    fn get_position(&self, map_pos: [i32; 2]) -> Vector3<Float> {
+        let resolution = self.height_map.get_resolution();
+        Vector3::new((map_pos[0] * resolution) as Float,
+                     (map_pos[1] * resolution) as Float,
+                     self.height_map.get(&map_pos) as Float)
+    }
+
+    // uvs are repeated per grid cell, the texture layer is the one of the triangle
+    fn add_vertex(&mut self, map_pos: [i32; 2], normal: Vector3<Float>, layer: u32) -> GLuint {
+        let index = (self.positions.len() / 3) as GLuint;
+        self.positions.extend(self.get_position(map_pos).as_array());
+        self.uvs.extend(&[map_pos[0] as Float, map_pos[1] as Float, layer as Float]);
+        self.normals.extend(normal.as_array());
+        self.indices.push(index);
+        index
+    }
+
+    fn add_index(&mut self, index: GLuint) {
+        self.indices.push(index);
+    }
+
+    fn finish(self) -> VertexBuffer {
+        let mut buffer = VertexBuffer::default();
+        buffer.add_float_buffer(self.positions, 0, 3);
+        buffer.add_float_buffer(self.uvs, 1, 3);
+        buffer.add_float_buffer(self.normals, 2, 3);
+        buffer.set_index_buffer(self.indices);
+        buffer
+    }
+}
+
+// hypotenuse end points of all triangles in the full hierarchy, the implicit binary tree
+// starts with the two halves of the grid square
+fn create_triangle_coords(size: i32) -> Vec<[i32; 4]> {
+    let tile_size = size - 1;
+    let triangle_count = (tile_size * tile_size * 2 - 2) as usize;
+    let mut coords = Vec::with_capacity(triangle_count);
+    for i in 0..triangle_count {
+        let mut id = i + 2;
+        let (mut a, mut b, mut c) = if id & 1 == 1 {
+            ([0, 0], [tile_size, tile_size], [tile_size, 0])
+        } else {
+            ([tile_size, tile_size], [0, 0], [0, tile_size])
+        };
+        id >>= 1;
+        while id > 1 {
+            let m = [(a[0] + b[0]) / 2, (a[1] + b[1]) / 2];
+            if id & 1 == 1 {
+                b = a;
+                a = c;
+            } else {
+                a = b;
+                b = c;
+            }
+            c = m;
+            id >>= 1;
+        }
+        coords.push([a[0], a[1], b[0], b[1]]);
+    }
+    coords
+}
+
+// texture layer of every grid cell, taken at its first corner like the grid mesher does
+fn get_cell_layers(origin: ChunkPos, architect: &Architect, height_map: &HeightMap) -> Vec<u32> {
+    let cell_count = height_map.get_size() - 1;
+    let resolution = height_map.get_resolution();
+    let mut layers = Vec::with_capacity((cell_count * cell_count) as usize);
+    for y in 0..cell_count {
+        for x in 0..cell_count {
+            layers.push(architect.get_terrain(origin.get_sample_world_pos([x, y], resolution)).get_layer());
+        }
+    }
+    layers
+}
+
+// the cell containing the centroid, a triangle, which is never split, lies within one cell
+// or covers cells of a single layer
+fn get_triangle_layer(cell_layers: &[u32], size: i32, triangle: &[[i32; 2]; 3]) -> u32 {
+    let cell_count = size - 1;
+    let cell = [0, 1].iter()
+        .map(|i| {
+            let centroid = (triangle[0][*i] + triangle[1][*i] + triangle[2][*i]) as f64 / 3.;
+            i32::min(centroid.floor() as i32, cell_count - 1)
+        })
+        .collect::<Vec<i32>>();
+    cell_layers[(cell[0] + cell[1] * cell_count) as usize]
+}
+
+// error at each hypotenuse midpoint, including the errors of all descendant triangles,
+// triangles at the borders or covering several layers always get split
+fn calculate_errors(height_map: &HeightMap, coords: &[[i32; 4]], cell_layers: &[u32]) -> Vec<f64> {
+    let size = height_map.get_size();
+    let mut errors = vec![0.; (size * size) as usize];
+    // the two triangles sharing a hypotenuse share its midpoint as well
+    let mut layers = vec![CoveredLayer::Unknown; (size * size) as usize];
+    // infinite errors at the border midpoints keep the borders at full resolution
+    for i in 0..size {
+        for border_pos in [[i, 0], [i, size - 1], [0, i], [size - 1, i]].iter() {
+            errors[(border_pos[0] + border_pos[1] * size) as usize] = f64::INFINITY;
+        }
+    }
+    let tile_size = size - 1;
+    let parent_count = coords.len() - (tile_size * tile_size) as usize;
+    for (i, coord) in coords.iter().enumerate().rev() {
+        let a = [coord[0], coord[1]];
+        let b = [coord[2], coord[3]];
+        let m = [(a[0] + b[0]) / 2, (a[1] + b[1]) / 2];
+        let c = [m[0] + m[1] - a[1], m[1] + a[0] - m[0]];
+        let interpolated = (height_map.get(&a) + height_map.get(&b)) / 2.;
+        let middle_index = (m[0] + m[1] * size) as usize;
+        let mut error = f64::max(errors[middle_index], (interpolated - height_map.get(&m)).abs());
+        let layer = if i < parent_count {
+            let left_child = ((a[0] + c[0]) / 2 + (a[1] + c[1]) / 2 * size) as usize;
+            let right_child = ((b[0] + c[0]) / 2 + (b[1] + c[1]) / 2 * size) as usize;
+            error = f64::max(error, f64::max(errors[left_child], errors[right_child]));
+            layers[left_child].merge(layers[right_child])
+        } else {
+            // the halves of the smallest triangles lie within single cells
+            let left_layer = get_triangle_layer(cell_layers, size, &[c, a, m]);
+            let right_layer = get_triangle_layer(cell_layers, size, &[b, c, m]);
+            CoveredLayer::Single(left_layer).merge(CoveredLayer::Single(right_layer))
+        };
+        layers[middle_index] = layers[middle_index].merge(layer);
+        if layers[middle_index] == CoveredLayer::Mixed {
+            error = f64::INFINITY;
+        }
+        errors[middle_index] = error;
+    }
+    errors
+}
+
+fn collect_triangles(
+    errors: &[f64],
+    size: i32,
+    max_error: f64,
+    triangle: [[i32; 2]; 3],
+    triangles: &mut Vec<[[i32; 2]; 3]>) {
+    let [a, b, c] = triangle;
+    let m = [(a[0] + b[0]) / 2, (a[1] + b[1]) / 2];
+    let splittable = (a[0] - c[0]).abs() + (a[1] - c[1]).abs() > 1;
+    if splittable && errors[(m[0] + m[1] * size) as usize] > max_error {
+        collect_triangles(errors, size, max_error, [c, a, m], triangles);
+        collect_triangles(errors, size, max_error, [b, c, m], triangles);
+    } else {
+        triangles.push(triangle);
+    }
+}
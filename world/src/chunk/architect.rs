@@ -1,14 +1,16 @@
 use rand::{ Rng, rngs::SmallRng, SeedableRng };
+use glm::{ Vector3, normalize };
 
 use core::Float;
 use crate::noise::{ Noise, OctavedNoise };
 use crate::{ Terrain, TerrainType, TerrainSet };
-use super::height_map::HeightMap;
-use super::get_world_pos;
+use super::height_map::{ HeightMap, HeightFormat };
+use crate::{ ChunkPos, WorldPos };
 
 pub struct Architect {
     height_noise: OctavedNoise,
     mountain_noise: OctavedNoise,
+    forest_noise: OctavedNoise,
     terrain_set: TerrainSet
 }
 
@@ -28,27 +30,51 @@ impl Architect {
         mountain_noise.set_roughness(2.);
         mountain_noise.set_range([-1., 1.]);
 
+        let mut forest_noise = OctavedNoise::from_rng(&mut local_rng);
+        forest_noise.set_octaves(3);
+        forest_noise.set_scale(4e-3);
+        forest_noise.set_roughness(0.5);
+        forest_noise.set_range([-1., 1.]);
+
         Self {
             height_noise: height_noise,
             mountain_noise: mountain_noise,
+            forest_noise: forest_noise,
             terrain_set: terrain_set.clone()
         }
     }
 
-    pub fn create_height_map(&self, chunk_pos: [i32; 2], chunk_size: i32, resolution: i32) -> HeightMap {
-        let size = chunk_size + 1;
-        let mut height_map = HeightMap::new(size, resolution);
+    pub fn create_height_map(&self, chunk_pos: ChunkPos, chunk_size: i32, resolution: i32, format: HeightFormat) -> HeightMap {
+        HeightMap::from_fn(chunk_size + 1, resolution, format, |pos| {
+            self.get_height(chunk_pos.get_sample_world_pos(pos, resolution))
+        })
+    }
+
+    // vertex normals by central differences, the heights beyond the chunk border are generated,
+    // so that normals of neighbouring chunks match
+    pub fn create_normals(&self, chunk_pos: ChunkPos, height_map: &HeightMap) -> Vec<Vector3<Float>> {
+        let size = height_map.get_size();
+        let resolution = height_map.get_resolution();
+        let get_ring_height = |x: i32, y: i32| {
+            if x >= 0 && y >= 0 && x < size && y < size {
+                height_map.get(&[x, y])
+            } else {
+                self.get_height(chunk_pos.get_sample_world_pos([x, y], resolution))
+            }
+        };
+        let mut normals = Vec::with_capacity((size * size) as usize);
         for y in 0..size {
             for x in 0..size {
-                let abs_pos = get_world_pos(&chunk_pos, &[x, y], resolution);
-                height_map.set(&[x, y], self.get_height(abs_pos));
+                let diff_x = get_ring_height(x - 1, y) - get_ring_height(x + 1, y);
+                let diff_y = get_ring_height(x, y - 1) - get_ring_height(x, y + 1);
+                normals.push(normalize(Vector3::new(diff_x as Float, diff_y as Float, (2 * resolution) as Float)));
             }
         }
-        height_map
+        normals
     }
 
-    pub fn get_terrain(&self, absolute_pos: [Float; 2]) -> &Terrain {
-        let mountain_val = self.mountain_noise.get_noise([absolute_pos[0] as f64, absolute_pos[1] as f64]);
+    pub fn get_terrain(&self, world_pos: WorldPos) -> &Terrain {
+        let mountain_val = self.mountain_noise.get_noise(world_pos.to_array());
         let terrain = if mountain_val > 0.5 {
             self.terrain_set.get(&TerrainType::Rock)
         } else {
@@ -63,16 +89,39 @@ impl Architect {
         }
     }
 
-    fn get_mountain_factor(&self, absolute_pos: [Float; 2]) -> f64 {
-        match self.mountain_noise.get_noise([absolute_pos[0] as f64, absolute_pos[1] as f64]) {
+    // vegetation density between 0 and 1, forests and clearings alternate, rock stays mostly bare
+    pub fn get_vegetation_density(&self, world_pos: WorldPos) -> f64 {
+        let terrain_factor = match self.get_terrain(world_pos).get_type() {
+            TerrainType::Grass => 1.,
+            TerrainType::Mud => 0.5,
+            TerrainType::Rock => 0.05
+        };
+        let forest_val = self.forest_noise.get_noise(world_pos.to_array());
+        let forest_factor = f64::min(f64::max((forest_val + 0.2) / 0.6, 0.), 1.);
+        terrain_factor * (0.1 + 0.9 * forest_factor)
+    }
+
+    pub fn get_normal(&self, world_pos: WorldPos) -> Vector3<Float> {
+        calculate_normal(world_pos, |pos| self.get_height(pos))
+    }
+
+    fn get_mountain_factor(&self, world_pos: WorldPos) -> f64 {
+        match self.mountain_noise.get_noise(world_pos.to_array()) {
             val if val > 0. => 1. +  (10. * val.powf(2.)),
             _ => 1.
         }
     }
 
-    fn get_height(&self, absolute_pos: [Float; 2]) -> f64 {
-        let raw_height = self.height_noise.get_noise([absolute_pos[0] as f64, absolute_pos[1] as f64]);
-        let mountain_factor = self.get_mountain_factor(absolute_pos);
+    pub fn get_height(&self, world_pos: WorldPos) -> f64 {
+        let raw_height = self.height_noise.get_noise(world_pos.to_array());
+        let mountain_factor = self.get_mountain_factor(world_pos);
         mountain_factor * raw_height
     }
 }
+
+// surface normal by central differences of the heights one unit around the position
+pub fn calculate_normal<F: Fn(WorldPos) -> f64>(world_pos: WorldPos, get_height: F) -> Vector3<Float> {
+    let diff_x = get_height(world_pos.offset(-1., 0.)) - get_height(world_pos.offset(1., 0.));
+    let diff_y = get_height(world_pos.offset(0., -1.)) - get_height(world_pos.offset(0., 1.));
+    normalize(Vector3::new(diff_x as Float, diff_y as Float, 2.))
+}
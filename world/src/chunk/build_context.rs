@@ -0,0 +1,93 @@
+use std::sync::{ Arc, RwLock };
+
+use core::ObjectManager;
+use super::{ Architect, AdaptiveMesher, GridMesher, HeightFormat, MeshSettings, Spawner, CHUNK_SIZE, get_lod_resolution };
+
+// everything shared by the workers to build chunks
+pub struct BuildContext {
+    architect: Arc<Architect>,
+    object_manager: Arc<ObjectManager>,
+    grid_mesher: GridMesher,
+    adaptive_mesher: Option<AdaptiveMesher>,
+    height_format: HeightFormat,
+    // replaced, when the spawn rules change, chunks being built keep the previous ones
+    spawners: RwLock<Arc<Vec<Spawner>>>,
+    spawn_seed: u64,
+    random_state: [u8; 16]
+}
+
+impl BuildContext {
+    pub fn new(
+        architect: Arc<Architect>,
+        object_manager: Arc<ObjectManager>,
+        mesh_settings: MeshSettings,
+        random_state: [u8; 16]) -> Self {
+        let cell_counts: Vec<i32> = (0..3).map(|lod| CHUNK_SIZE / get_lod_resolution(lod)).collect();
+        let grid_sizes: Vec<i32> = cell_counts.iter().map(|count| count + 1).collect();
+        let flat_shading = mesh_settings.is_flat_shading();
+        let grid_mesher = GridMesher::new(flat_shading, &cell_counts);
+        let adaptive_mesher = mesh_settings.get_max_vertical_errors()
+            .map(|errors| AdaptiveMesher::new(flat_shading, errors, &grid_sizes));
+        let mut spawn_seed = [0; 8];
+        spawn_seed.copy_from_slice(&random_state[..8]);
+        let spawn_seed = u64::from_le_bytes(spawn_seed);
+        let spawners = create_spawners(&object_manager, spawn_seed);
+        Self {
+            architect: architect,
+            object_manager: object_manager,
+            grid_mesher: grid_mesher,
+            adaptive_mesher: adaptive_mesher,
+            height_format: mesh_settings.get_height_format(),
+            spawners: RwLock::new(Arc::new(spawners)),
+            spawn_seed: spawn_seed,
+            random_state: random_state
+        }
+    }
+
+    pub fn get_architect(&self) -> &Arc<Architect> {
+        &self.architect
+    }
+
+    pub fn get_object_manager(&self) -> &ObjectManager {
+        &self.object_manager
+    }
+
+    pub fn get_grid_mesher(&self) -> &GridMesher {
+        &self.grid_mesher
+    }
+
+    pub fn get_adaptive_mesher(&self) -> Option<&AdaptiveMesher> {
+        self.adaptive_mesher.as_ref()
+    }
+
+    pub fn get_height_format(&self) -> HeightFormat {
+        self.height_format
+    }
+
+    pub fn get_spawners(&self) -> Arc<Vec<Spawner>> {
+        // the lock is only held for swapping the spawners, so a poisoned one still holds valid spawners
+        match self.spawners.read() {
+            Ok(spawners) => spawners.clone(),
+            Err(poisoned) => poisoned.into_inner().clone()
+        }
+    }
+
+    // takes the current spawn rules of the object manager, e.g. after a reload of the prototype file
+    pub fn update_spawners(&self) {
+        let spawners = Arc::new(create_spawners(&self.object_manager, self.spawn_seed));
+        match self.spawners.write() {
+            Ok(mut current) => *current = spawners,
+            Err(poisoned) => *poisoned.into_inner() = spawners
+        }
+    }
+
+    pub fn get_random_state(&self) -> &[u8; 16] {
+        &self.random_state
+    }
+}
+
+fn create_spawners(object_manager: &ObjectManager, spawn_seed: u64) -> Vec<Spawner> {
+    object_manager.get_spawn_rules().into_iter()
+        .map(|(name, rule)| Spawner::new(&name, rule, spawn_seed))
+        .collect()
+}
@@ -0,0 +1,11 @@
+use super::{ChunkBuilder, ChunkError};
+use crate::ChunkPos;
+
+pub enum BuildResult {
+    Finished(ChunkBuilder),
+    Failed {
+        pos: ChunkPos,
+        lod: u8,
+        error: ChunkError,
+    },
+}
@@ -1,42 +1,69 @@
+use std::collections::BTreeMap;
+use std::mem::size_of;
+
 use glm::{Vector2, Vector3};
 
-use super::{HeightMap, CHUNK_SIZE};
+use super::{HeightMap, MinMaxTree, ObjectGrid, CHUNK_SIZE};
+use crate::{ChunkHeights, ChunkPos, InstanceBatch, ObjectId};
 use core::graphics::{GraphicsError, Mesh};
 use core::traits::{RenderInfo, Renderable, Translatable};
 use core::{BoundingBox, Float, Model, Object};
 
 pub struct Chunk {
-    pos: [i32; 2],
+    pos: ChunkPos,
     model: Model,
     mesh: Mesh,
     height_map: HeightMap,
+    min_max_tree: MinMaxTree,
     lod: u8,
-    tree_list: Vec<Object>,
+    // removed objects leave an empty slot, so slots stay valid as object ids
+    object_list: Vec<Option<Object>>,
+    // objects inserted at runtime by their slots, which start at ObjectId::FIRST_INSERTED_SLOT
+    inserted_objects: BTreeMap<usize, Object>,
+    object_grid: ObjectGrid,
     bounding_box: BoundingBox,
+    origin: ChunkPos,
 }
 
 impl Chunk {
-    pub fn new(pos: [i32; 2], height_map: HeightMap, lod: u8, mesh: Mesh) -> Self {
-        let mut model = Model::default();
-        model.set_translation(Vector3::new(
-            (pos[0] * CHUNK_SIZE) as Float,
-            (pos[1] * CHUNK_SIZE) as Float,
-            0.,
-        ));
+    pub fn new(pos: ChunkPos, height_map: HeightMap, lod: u8, mesh: Mesh) -> Self {
         let bounding_box = build_bounding_box(&height_map);
+        let min_max_tree = MinMaxTree::new(&height_map);
 
         Self {
             pos: pos,
-            model: model,
+            model: Model::default(),
             mesh: mesh,
             height_map: height_map,
+            min_max_tree: min_max_tree,
             lod: lod,
-            tree_list: Vec::new(),
+            object_list: Vec::new(),
+            inserted_objects: BTreeMap::new(),
+            object_grid: ObjectGrid::default(),
             bounding_box: bounding_box,
+            origin: pos,
         }
     }
 
-    pub fn get_pos(&self) -> [i32; 2] {
+    // moves the chunk and its objects relative to the floating origin chunk,
+    // objects are added relative to the chunk
+    pub fn set_origin(&mut self, origin: ChunkPos) {
+        let shift = self.origin - origin;
+        let offset = Vector3::new(
+            (shift.get_x() * CHUNK_SIZE) as Float,
+            (shift.get_y() * CHUNK_SIZE) as Float,
+            0.,
+        );
+        self.model.mod_translation(offset);
+        self.object_list
+            .iter_mut()
+            .flatten()
+            .chain(self.inserted_objects.values_mut())
+            .for_each(|o| o.mod_translation(offset));
+        self.origin = origin;
+    }
+
+    pub fn get_pos(&self) -> ChunkPos {
         self.pos
     }
 
@@ -48,17 +75,124 @@ impl Chunk {
         self.mesh.get_vertex_count()
     }
 
-    pub fn get_height(&self, world_pos: Vector2<Float>) -> f64 {
-        let chunk_pos = self.model.get_translation();
-        let relative_pos = [
-            (world_pos.x as f64) - (chunk_pos.x as f64),
-            (world_pos.y as f64) - (chunk_pos.y as f64),
-        ];
-        self.height_map.get_interpolated_height(relative_pos)
+    // returns the distance along the direction and the surface normal of the nearest terrain hit
+    pub fn raycast_terrain(
+        &self,
+        origin: Vector3<Float>,
+        direction: Vector3<Float>,
+        max_distance: Float,
+    ) -> Option<(Float, Vector3<Float>)> {
+        let local_origin = origin - self.model.get_translation();
+        self.min_max_tree
+            .intersect_ray(&self.height_map, local_origin, direction, max_distance)
+    }
+
+    // objects with their slots
+    pub fn get_objects(&self) -> impl Iterator<Item = (usize, &Object)> {
+        self.object_list
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, o)| o.as_ref().map(|o| (slot, o)))
+            .chain(self.inserted_objects.iter().map(|(slot, o)| (*slot, o)))
+    }
+
+    pub fn get_object(&self, slot: usize) -> Option<&Object> {
+        if slot >= ObjectId::FIRST_INSERTED_SLOT {
+            self.inserted_objects.get(&slot)
+        } else {
+            self.object_list.get(slot).and_then(|o| o.as_ref())
+        }
+    }
+
+    // objects with their slots, whose bounds may overlap the rectangle given relative to the floating origin
+    pub fn get_objects_in_rect(
+        &self,
+        min: Vector2<Float>,
+        max: Vector2<Float>,
+    ) -> impl Iterator<Item = (usize, &Object)> {
+        let offset = self.get_offset().truncate(2);
+        self.object_grid
+            .query(min - offset, max - offset)
+            .into_iter()
+            .filter_map(move |slot| self.get_object(slot).map(|o| (slot, o)))
+    }
+
+    // position of the chunk relative to the floating origin
+    pub fn get_offset(&self) -> Vector3<Float> {
+        self.model.get_translation()
+    }
+
+    pub fn get_heights(&self) -> ChunkHeights<'_> {
+        ChunkHeights::new(self.pos, &self.height_map)
+    }
+
+    pub fn get_height_map_memory(&self) -> usize {
+        self.height_map.get_memory_usage()
+    }
+
+    pub fn get_memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.height_map.get_memory_usage()
+            + self.mesh.get_buffer_size()
+            + self.object_list.len() * size_of::<Option<Object>>()
+            + self.inserted_objects.len() * size_of::<(usize, Object)>()
+            + self.object_grid.get_memory_usage()
+    }
+
+    // adds a spawned object, which is positioned relative to the chunk, returns its slot
+    pub fn add_object(&mut self, object: Object) -> usize {
+        let slot = self.object_list.len();
+        debug_assert!(slot < ObjectId::FIRST_INSERTED_SLOT);
+        self.insert_object(slot, object);
+        slot
+    }
+
+    // replaces an object already in the slot
+    pub fn insert_object(&mut self, slot: usize, mut object: Object) {
+        self.remove_object(slot);
+        if slot < ObjectId::FIRST_INSERTED_SLOT && slot >= self.object_list.len() {
+            self.object_list.resize_with(slot + 1, || None);
+        }
+        let offset = self.get_offset();
+        object.mod_translation(offset);
+        let bounds = object.get_bounds();
+        self.object_grid.insert(
+            slot,
+            (bounds.get_min() - offset).truncate(2),
+            (bounds.get_max() - offset).truncate(2),
+        );
+        if slot >= ObjectId::FIRST_INSERTED_SLOT {
+            self.inserted_objects.insert(slot, object);
+        } else {
+            self.object_list[slot] = Some(object);
+        }
+    }
+
+    pub fn remove_object(&mut self, slot: usize) -> Option<Object> {
+        let object = if slot >= ObjectId::FIRST_INSERTED_SLOT {
+            self.inserted_objects.remove(&slot)?
+        } else {
+            self.object_list.get_mut(slot)?.take()?
+        };
+        self.object_grid.remove(slot);
+        Some(object)
+    }
+
+    // objects are only drawn at full detail and for visible chunks
+    pub fn collect_objects(&self, info: &RenderInfo, batch: &mut InstanceBatch) {
+        if info.get_lod() != 0 || !self.is_visible(info) {
+            return;
+        }
+        self.get_objects().for_each(|(_, o)| batch.add(o));
+    }
+
+    fn is_visible(&self, info: &RenderInfo) -> bool {
+        let mvp = info.get_camera().create_mvp_matrix(&self.model);
+        self.bounding_box.is_visible(mvp)
     }
 
-    pub fn add_tree(&mut self, tree_object: Object) {
-        self.tree_list.push(tree_object);
+    pub fn into_height_map(self) -> HeightMap {
+        self.height_map
     }
 }
 
@@ -70,11 +204,6 @@ impl Renderable for Chunk {
             shader.set_resource_mat4("mvp", &mvp)?;
             shader.set_resource_mat4("model", self.model.get_matrix_ref())?;
             self.mesh.render(info)?;
-            if info.get_lod() == 0 {
-                for tree in &self.tree_list {
-                    tree.render(info)?;
-                }
-            }
         }
         Ok(())
     }
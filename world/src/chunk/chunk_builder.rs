@@ -1,131 +1,103 @@
 use std::convert::TryFrom;
-use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::sync::atomic::{ AtomicBool, Ordering };
 
 use rand::{ Rng, rngs::SmallRng, SeedableRng };
-use glm::{ Vector2, Vector3 };
 
-use core::{Float, Object, ObjectManager };
-use core::traits::{ Translatable, Rotatable, Scalable };
-use core::graphics::mesh::{ Vertex, Triangle, Mesh, VertexBuffer };
-use super::{ Chunk, ChunkError, HeightMap, Architect, CHUNK_SIZE, get_world_pos };
+use core::Object;
+use core::graphics::mesh::{ Mesh, VertexBuffer };
+use crate::ChunkPos;
+use super::{ Chunk, ChunkError, ChunkRequest, HeightMap, BuildContext, CHUNK_SIZE, get_lod_resolution };
 
 pub struct ChunkBuilder {
-    pos: [i32; 2],
+    pos: ChunkPos,
     lod: u8,
     height_map: HeightMap,
     surface_vertices: VertexBuffer,
-    tree_list: Vec<Object>
+    object_list: Vec<Object>,
+    cancelled: Arc<AtomicBool>
 }
 
 impl ChunkBuilder {
 
-    pub fn new(
-        pos: [i32; 2],
-        lod: u8,
-        architect: &Architect,
-        object_manager: &ObjectManager,
-        random_state: &[u8; 16]) -> Result<Self, ChunkError> {
+    pub fn new(mut request: ChunkRequest, context: &BuildContext) -> Result<Self, ChunkError> {
+        let architect = context.get_architect();
 
-        let height_map = match lod {
-            0 => architect.create_height_map(pos, CHUNK_SIZE, 1),
-            _ => architect.create_height_map(pos, CHUNK_SIZE / 8, 8),
+        let pos = request.get_pos();
+        let lod = request.get_lod();
+        let cancelled = request.get_cancel_flag();
+        let resolution = get_lod_resolution(lod);
+        let height_map = match request.take_height_map() {
+            Some(height_map) => {
+                debug_assert!(height_map.get_resolution() == resolution);
+                debug_assert!(height_map.get_format() == context.get_height_format());
+                height_map
+            },
+            None => architect.create_height_map(pos, CHUNK_SIZE / resolution, resolution, context.get_height_format())
+        };
+        let surface_buffer = match context.get_adaptive_mesher() {
+            Some(mesher) => mesher.create_buffer(pos, lod, architect, &height_map),
+            None => context.get_grid_mesher().create_buffer(pos, architect, &height_map)
         };
-        let surface_buffer = create_surface_buffer(pos, architect, &height_map);
         let mut builder = Self {
             pos: pos,
             lod: lod,
             height_map: height_map,
             surface_vertices: surface_buffer,
-            tree_list: Vec::new(),
+            object_list: Vec::new(),
+            cancelled: cancelled,
         };
 
         let mut seed: [u8; 16] = [0; 16];
-        seed.copy_from_slice(random_state);
+        seed.copy_from_slice(context.get_random_state());
+        let pos_array = pos.to_array();
         for i in 0..8 {
-            seed[i] += (pos[i / 4] >> (8 * (i % 4))) as u8;
+            seed[i] = seed[i].wrapping_add((pos_array[i / 4] >> (8 * (i % 4))) as u8);
         }
         let mut rng = SmallRng::from_seed(seed);
 
-        builder.load_trees(object_manager, &mut rng)?;
+        builder.load_objects(context, &mut rng)?;
         Ok(builder)
     }
 
+    pub fn get_pos(&self) -> ChunkPos {
+        self.pos
+    }
+
+    pub fn get_lod(&self) -> u8 {
+        self.lod
+    }
+
+    pub fn get_buffer_size(&self) -> usize {
+        self.surface_vertices.get_buffer_size()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
     pub fn finish(self) -> Result<Chunk, ChunkError> {
         let mesh = Mesh::try_from(self.surface_vertices)?;
         let mut chunk = Chunk::new(self.pos, self.height_map, self.lod, mesh);
-        self.tree_list.into_iter().for_each(|t| chunk.add_tree(t));
+        for object in self.object_list {
+            chunk.add_object(object);
+        }
         Ok(chunk)
     }
 
-    fn load_trees<R: Rng + ?Sized>(&mut self, object_manager: &ObjectManager, rng: &mut R) -> Result<(), ChunkError> {
+    // objects are placed by the spawn rules of their prototypes
+    fn load_objects<R: Rng + ?Sized>(&mut self, context: &BuildContext, rng: &mut R) -> Result<(), ChunkError> {
         if self.lod < 2 {
-            let resolution = self.height_map.get_resolution();
-            let size = self.height_map.get_size();
-            let tree_count = rng.gen_range(2, 20);
-            let mut positions: BTreeSet<[i32; 2]> = BTreeSet::default();
-            for _ in 0..tree_count {    // ignore if less trees, bc would tree would be spawned on same pos
-                positions.insert([rng.gen_range(0, size), rng.gen_range(0, size)]);
-            }
-            for rel_pos in positions.into_iter() {
-                let abs_pos = [((self.pos[0] * CHUNK_SIZE) + rel_pos[0] * resolution) as Float,
-                                ((self.pos[1] * CHUNK_SIZE) + rel_pos[1] * resolution) as Float];
-                let mut tree = object_manager.create_object("tree")?;
-                tree.set_translation(Vector3::new(abs_pos[0], abs_pos[1], self.height_map.get(&rel_pos) as Float));
-                let scale_xy = rng.gen_range(0.8, 1.2);
-                let scale_z = rng.gen_range(0.8, 1.4);
-                tree.set_scale(Vector3::new(scale_xy, scale_xy, scale_z));
-                let orientation = Vector3::new(
-                    rng.gen_range(-0.2, 0.2),
-                    rng.gen_range(-0.2, 0.2),
-                    rng.gen_range(-0.2, 0.2));
-                tree.set_rotation(orientation);
-                self.tree_list.push(tree);
+            for spawner in context.get_spawners().iter() {
+                let objects = spawner.spawn(
+                    self.pos,
+                    &self.height_map,
+                    context.get_architect(),
+                    context.get_object_manager(),
+                    rng)?;
+                self.object_list.extend(objects);
             }
         }
         Ok(())
     }
 }
-
-fn create_surface_buffer(origin: [i32; 2], architect: &Architect, height_map: &HeightMap) -> VertexBuffer {
-    let size = height_map.get_size();
-    let resolution = height_map.get_resolution();
-    let mut triangles: Vec<Triangle> = Vec::with_capacity((size * size * 2) as usize);
-    for y in 0..size - 1 {
-        for x in 0..size - 1 {
-            let abs_pos = get_world_pos(&origin, &[x, y], resolution);
-            let terrain = architect.get_terrain(abs_pos);
-            triangles.extend(&add_quad_triangles(&[x, y], height_map, terrain.get_layer()));
-        }
-    }
-    VertexBuffer::from(triangles.as_slice())
-}
-
-fn add_quad_triangles(offset: &[i32; 2], height_map: &HeightMap, texture_layer: u32) -> [Triangle; 2] {
-    const OFFSET: Float = 1.;
-    const VERTEX_OFFSETS: [[Float; 2]; 6] = [
-        [0., 0.],         [OFFSET, OFFSET], [0., OFFSET],
-        [OFFSET, OFFSET], [0., 0.],         [OFFSET, 0.]
-    ];
-    let mut triangles = [Triangle::default(),
-                         Triangle::default()];
-    let resolution: Float = height_map.get_resolution() as Float;
-
-    for i in 0..2 {
-        let mut vertices: [Vertex; 3] = [Vertex::default(),
-                                         Vertex::default(),
-                                         Vertex::default()];
-        for (vert, off) in vertices.iter_mut().zip(VERTEX_OFFSETS.iter().skip(i * 3).take(3)) {
-                let map_pos = [offset[0] + i32::max(0, i32::min(1, off[0] as i32)),
-                               offset[1] + i32::max(0, i32::min(1, off[1] as i32))];
-                let height = height_map.get(&map_pos);
-                vert.set_pos(Vector3::new((offset[0] as Float + off[0]) * resolution,
-                                          (offset[1] as Float + off[1]) * resolution,
-                                          height as Float));
-                debug_assert!(off[0] <= 1., off[1] <= 1.);
-                vert.set_uv(Vector2::new(off[0], off[1]));
-        }
-        triangles[i] = Triangle::new(vertices);
-    }
-    triangles.iter_mut().for_each(|t| t.set_uv_layer(texture_layer));
-    triangles
-}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use super::{get_lod_resolution, Chunk, HeightMap};
+use crate::ChunkPos;
+
+pub enum CachedChunk {
+    Complete(Chunk),
+    Surface(HeightMap),
+}
+
+struct CacheEntry {
+    content: CachedChunk,
+    lod: u8,
+    size: usize,
+    last_access: u64,
+}
+
+pub struct ChunkCache {
+    entries: BTreeMap<ChunkPos, CacheEntry>,
+    // positions by their last access, the first one gets evicted next
+    recency: BTreeMap<u64, ChunkPos>,
+    budget: usize,
+    used: usize,
+    keep_gpu: bool,
+    access_counter: u64,
+    hits: u32,
+    misses: u32,
+}
+
+impl ChunkCache {
+    pub fn new(budget: usize, keep_gpu: bool) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+            budget: budget,
+            used: 0,
+            keep_gpu: keep_gpu,
+            access_counter: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn insert(&mut self, chunk: Chunk) {
+        let pos = chunk.get_pos();
+        let lod = chunk.get_lod();
+        let content = if self.keep_gpu {
+            CachedChunk::Complete(chunk)
+        } else {
+            CachedChunk::Surface(chunk.into_height_map())
+        };
+        let size = match content {
+            CachedChunk::Complete(ref c) => c.get_memory_usage(),
+            CachedChunk::Surface(ref hm) => hm.get_memory_usage(),
+        };
+        if size > self.budget {
+            return;
+        }
+        self.remove(pos);
+        self.access_counter += 1;
+        self.used += size;
+        self.recency.insert(self.access_counter, pos);
+        self.entries.insert(
+            pos,
+            CacheEntry {
+                content: content,
+                lod: lod,
+                size: size,
+                last_access: self.access_counter,
+            },
+        );
+        self.evict();
+    }
+
+    // Returns the cached chunk content only if it can serve the requested lod,
+    // a cached height map can be reused by any lod of the same resolution.
+    pub fn take(&mut self, pos: ChunkPos, lod: u8) -> Option<CachedChunk> {
+        let usable = match self.entries.get(&pos) {
+            Some(entry) => match entry.content {
+                CachedChunk::Complete(_) => entry.lod == lod,
+                CachedChunk::Surface(ref hm) => hm.get_resolution() == get_lod_resolution(lod),
+            },
+            None => false,
+        };
+        if usable {
+            self.hits += 1;
+            self.remove(pos).map(|e| e.content)
+        } else {
+            self.misses += 1;
+            None
+        }
+    }
+
+    pub fn invalidate(&mut self, pos: ChunkPos) {
+        self.remove(pos);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.used = 0;
+    }
+
+    pub fn get_entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_used_memory(&self) -> usize {
+        self.used
+    }
+
+    pub fn get_budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn get_hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    fn remove(&mut self, pos: ChunkPos) -> Option<CacheEntry> {
+        let entry = self.entries.remove(&pos)?;
+        self.recency.remove(&entry.last_access);
+        self.used -= entry.size;
+        Some(entry)
+    }
+
+    fn evict(&mut self) {
+        while self.used > self.budget {
+            let oldest = self.recency.values().next().copied();
+            match oldest {
+                Some(pos) => {
+                    trace!("Evicting chunk {} from cache", pos);
+                    self.remove(pos);
+                }
+                None => break,
+            }
+        }
+    }
+}
@@ -3,12 +3,14 @@ use std::error::Error;
 
 use core::graphics::mesh::MeshError;
 use core::object::ObjectError;
+use crate::ChunkPos;
 
 #[derive(Debug)]
 pub enum ChunkError {
     Mesh(MeshError),
     Object(ObjectError),
-    NoBufferBuilt([i32; 2]),
+    NoBufferBuilt(ChunkPos),
+    BuildPanic(ChunkPos),
     MutexPoison
 }
 
@@ -32,6 +34,7 @@ impl Error for ChunkError {
             ChunkError::Mesh(_) => "mesh",
             ChunkError::Object(_) => "object",
             ChunkError::NoBufferBuilt(_) => "no buffer built",
+            ChunkError::BuildPanic(_) => "build panic",
             ChunkError::MutexPoison => "mutex poison"
         }
     }
@@ -41,6 +44,7 @@ impl Error for ChunkError {
             ChunkError::Mesh(ref err) => Some(err),
             ChunkError::Object(ref err) => Some(err),
             ChunkError::NoBufferBuilt(_) => None,
+            ChunkError::BuildPanic(_) => None,
             ChunkError::MutexPoison => None
         }
     }
@@ -51,7 +55,8 @@ impl fmt::Display for ChunkError {
         match *self {
             ChunkError::Mesh(ref err) => write!(f, "{}/{}", self.description(), err),
             ChunkError::Object(ref err) => write!(f, "{}/{}", self.description(), err),
-            ChunkError::NoBufferBuilt(chunk_pos) => write!(f, "{}: chunk pos = {}/{}", self.description(), chunk_pos[0], chunk_pos[1]),
+            ChunkError::NoBufferBuilt(chunk_pos) => write!(f, "{}: chunk pos = {}", self.description(), chunk_pos),
+            ChunkError::BuildPanic(chunk_pos) => write!(f, "{}: chunk pos = {}", self.description(), chunk_pos),
             ChunkError::MutexPoison => write!(f, "{}", self.description())
         }
     }
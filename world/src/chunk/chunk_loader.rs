@@ -1,73 +1,101 @@
 use std::sync::{ Arc, Mutex };
-use std::collections::{ BTreeMap, BTreeSet, VecDeque };
+use std::collections::{ BTreeMap, VecDeque };
 use std::thread;
+use std::time::Instant;
 use std::sync::atomic::{ AtomicBool, Ordering };
 
+use glm::{ Vector2, Vector3 };
 use rand::{ Rng };
 
-use core::{ ObjectManager };
-use crate::TerrainSet;
-use super::{ Chunk, ChunkBuilder, Architect, ChunkError, BuildStats, Worker };
+use core::{ Float, ObjectManager };
+use crate::{ TerrainSet, ChunkPos };
+use super::{ Chunk, ChunkBuilder, ChunkRequest, ChunkQueue, Architect, ChunkError, BuildContext, BuildStats, BuildResult,
+             MeshSettings, OutputQueue, RetryQueue, Signaled, UploadBudget, Worker };
+
+// a thread dying again after this many respawns has a lasting cause, so it stays dead
+const MAX_WORKER_RESPAWNS: u32 = 5;
 
 pub struct ChunkLoader {
     stop: Arc<AtomicBool>,
-    architect: Arc<Architect>,
-    object_manager: Arc<ObjectManager>,
-    input_queue: Arc<Mutex<VecDeque<([i32; 2], u8)>>>,
-    output_queue: Arc<Mutex<Vec<ChunkBuilder>>>,
+    context: Arc<BuildContext>,
+    input_queue: Arc<Signaled<ChunkQueue>>,
+    output_queue: Arc<Signaled<OutputQueue>>,
+    // bounded like the output queue, so workers block when uploads fall behind
+    upload_queue: VecDeque<ChunkBuilder>,
+    upload_capacity: usize,
+    upload_budget: UploadBudget,
     build_stats: Arc<Mutex<BuildStats>>,
-    handeled_positions: BTreeSet<[i32; 2]>,
+    pending: BTreeMap<ChunkPos, Arc<AtomicBool>>,
+    retry_queue: RetryQueue,
+    worker: Option<Worker>,
     thread_handles: Vec<thread::JoinHandle<()>>,
-    random_state: [u8; 16],
+    // respawns of the thread in the same slot as its handle
+    worker_respawns: Vec<u32>,
+    failed_build_count: u32,
+    respawn_count: u32,
 }
 
 
 impl ChunkLoader {
-    pub fn new<R: Rng + ?Sized>(rng: &mut R, object_manager: Arc<ObjectManager>, terrain_set: &TerrainSet) -> Self {
+    pub fn new<R: Rng + ?Sized>(
+        rng: &mut R,
+        object_manager: Arc<ObjectManager>,
+        terrain_set: &TerrainSet,
+        output_capacity: usize,
+        upload_budget: UploadBudget,
+        retry_queue: RetryQueue,
+        mesh_settings: MeshSettings) -> Self {
+        let architect = Arc::new(Architect::from_rng(rng, terrain_set));
         let mut random_state = [0; 16];
         rng.fill_bytes(&mut random_state);
         Self {
             stop: Arc::new(AtomicBool::new(false)),
-            architect: Arc::new(Architect::from_rng(rng, terrain_set)),
-            object_manager: object_manager,
-            input_queue: Arc::new(Mutex::new(VecDeque::new())),
-            output_queue: Arc::new(Mutex::new(Vec::new())),
+            context: Arc::new(BuildContext::new(architect, object_manager, mesh_settings, random_state)),
+            input_queue: Arc::new(Signaled::new(ChunkQueue::default())),
+            output_queue: Arc::new(Signaled::new(OutputQueue::new(output_capacity))),
+            upload_queue: VecDeque::new(),
+            upload_capacity: output_capacity,
+            upload_budget: upload_budget,
             build_stats: Arc::new(Mutex::new(BuildStats::default())),
-            handeled_positions: BTreeSet::new(),
+            pending: BTreeMap::new(),
+            retry_queue: retry_queue,
+            worker: None,
             thread_handles: Vec::new(),
-            random_state: random_state
+            worker_respawns: Vec::new(),
+            failed_build_count: 0,
+            respawn_count: 0
         }
     }
     pub fn start(&mut self, thread_count: usize) {
         if !self.thread_handles.is_empty() {
             warn!("Starting chunk loader threads, but threads already running");
         }
-        self.stop.load(Ordering::Relaxed);
+        self.stop.store(false, Ordering::Relaxed);
         let worker = Worker::new(
-            self.architect.clone(),
-            self.object_manager.clone(),
+            self.context.clone(),
             self.stop.clone(),
             self.input_queue.clone(),
             self.output_queue.clone(),
-            self.build_stats.clone(),
-            self.random_state
+            self.build_stats.clone()
         );
         for _i in 0..thread_count {
-            let next_worker = worker.clone();
-            let handle = thread::spawn(move || {
-                match next_worker.work() {
-                    Ok(_) => trace!("Worker finished successfully"),
-                    Err(e) =>  error!("Worker error: {}", e)
-                 }
-            });
-            self.thread_handles.push(handle);
+            self.thread_handles.push(spawn_worker(worker.clone()));
+            self.worker_respawns.push(0);
         }
+        self.worker = Some(worker);
         info!("Started chunk loader with {} threads", thread_count);
     }
 
     pub fn stop(&mut self) {
         info!("Stopping chunk loader threads");
         self.stop.store(true, Ordering::Relaxed);
+        // notifying while holding the locks, so no worker can miss the stop signal
+        if let Ok(_guard) = self.input_queue.lock() {
+            self.input_queue.notify_all();
+        }
+        if let Ok(_guard) = self.output_queue.lock() {
+            self.output_queue.notify_all();
+        }
         let mut stop_count = 0;
         while let Some(handle) = self.thread_handles.pop() {
             match handle.join() {
@@ -75,39 +103,184 @@ impl ChunkLoader {
                 Err(_) => warn!("Thread to join panicked")
             }
         }
+        self.worker_respawns.clear();
         info!("Stopped {} chunk loader threads", stop_count);
     }
 
-    pub fn get(&mut self) -> Result<BTreeMap<[i32; 2], Chunk>, ChunkError> {
+    // uploads finished chunks to the gpu until the upload budget is exhausted,
+    // remaining chunks are kept for the following calls
+    pub fn get(&mut self) -> Result<BTreeMap<ChunkPos, Chunk>, ChunkError> {
+        self.respawn_dead_workers();
+        self.request_due_retries()?;
+
+        let free_count = self.upload_capacity.saturating_sub(self.upload_queue.len());
+        let results = self.output_queue.lock()?.drain(free_count);
+        if !results.is_empty() {
+            self.output_queue.notify_all();
+        }
+        for result in results {
+            match result {
+                BuildResult::Finished(cb) => {
+                    if !cb.is_cancelled() {
+                        self.upload_queue.push_back(cb);
+                    }
+                },
+                BuildResult::Failed { pos, lod, error } => self.handle_failure(pos, lod, error)
+            }
+        }
+
         let mut chunks = BTreeMap::new();
-        match self.output_queue.lock() {
-            Ok(mut guard) => {
-                while let Some(cb) = (*guard).pop() {
-                    let chunk = cb.finish()?;
-                    let pos = chunk.get_pos();
-                    self.handeled_positions.remove(&pos);
+        let upload_start = Instant::now();
+        let mut uploaded_bytes = 0;
+        while !self.upload_budget.is_exhausted(&upload_start, uploaded_bytes) {
+            let cb = match self.upload_queue.pop_front() {
+                Some(cb) => cb,
+                None => break
+            };
+            if cb.is_cancelled() {
+                continue;
+            }
+            let pos = cb.get_pos();
+            let lod = cb.get_lod();
+            uploaded_bytes += cb.get_buffer_size();
+            match cb.finish() {
+                Ok(chunk) => {
+                    self.pending.remove(&pos);
+                    self.retry_queue.remove(pos);
                     chunks.insert(pos, chunk);
-                }
-            },
-            Err(_poisoned) => { return Err(ChunkError::MutexPoison); }
+                },
+                Err(e) => self.handle_failure(pos, lod, e)
+            }
         }
         Ok(chunks)
     }
 
-    pub fn request(&mut self, chunk_pos: &[([i32; 2], u8)]) -> Result<(), ChunkError> {
+    pub fn request(&mut self, requests: Vec<ChunkRequest>) -> Result<(), ChunkError> {
+        let mut guard = self.input_queue.lock()?;
+        for request in requests {
+            if !self.pending.contains_key(&request.get_pos()) {
+                self.pending.insert(request.get_pos(), request.get_cancel_flag());
+                (*guard).push(request);
+            }
+        }
+        self.input_queue.notify_all();
+        Ok(())
+    }
+
+    pub fn set_focus(&mut self, center: Vector2<Float>, direction: Vector3<Float>) -> Result<(), ChunkError> {
+        self.input_queue.lock()?.set_focus(center, direction);
+        Ok(())
+    }
+
+    // cancels queued and currently built chunks, which are not within the radius around the center
+    pub fn cancel_distant(&mut self, center: ChunkPos, radius: i32) -> Result<usize, ChunkError> {
+        let cancel_list: Vec<ChunkPos> = self.pending.keys()
+            .filter(|pos| pos.get_distance(center).round() as i32 >= radius)
+            .cloned()
+            .collect();
+        let mut guard = self.input_queue.lock()?;
+        for pos in cancel_list.iter() {
+            (*guard).cancel(*pos);
+            self.retry_queue.remove(*pos);
+            if let Some(flag) = self.pending.remove(pos) {
+                flag.store(true, Ordering::Relaxed);
+            }
+        }
+        Ok(cancel_list.len())
+    }
+
+    // chunks requested from now on are spawned by the current spawn rules of the object manager
+    pub fn update_spawners(&self) {
+        self.context.update_spawners();
+    }
+
+    pub fn get_architect(&self) -> Arc<Architect> {
+        self.context.get_architect().clone()
+    }
+
+    pub fn get_failed_build_count(&self) -> u32 {
+        self.failed_build_count
+    }
+
+    pub fn get_retry_count(&self) -> usize {
+        self.retry_queue.len()
+    }
+
+    pub fn get_respawn_count(&self) -> u32 {
+        self.respawn_count
+    }
+
+    fn handle_failure(&mut self, pos: ChunkPos, lod: u8, error: ChunkError) {
+        self.failed_build_count += 1;
+        if !self.pending.contains_key(&pos) {
+            return;     // got cancelled meanwhile
+        }
+        if self.retry_queue.schedule(pos, lod) {
+            warn!("Chunk build failed for {}, scheduling retry: {}", pos, error);
+        } else {
+            error!("Chunk build failed for {}, giving up: {}", pos, error);
+            self.pending.remove(&pos);
+        }
+    }
+
+    fn request_due_retries(&mut self) -> Result<(), ChunkError> {
+        let due_list = self.retry_queue.take_due();
+        if due_list.is_empty() {
+            return Ok(());
+        }
+        let mut guard = self.input_queue.lock()?;
+        for (pos, lod, attempt) in due_list {
+            if !self.pending.contains_key(&pos) {
+                self.retry_queue.remove(pos);
+                continue;
+            }
+            trace!("Retrying chunk {}, attempt {}", pos, attempt);
+            let request = ChunkRequest::new(pos, lod);
+            self.pending.insert(pos, request.get_cancel_flag());
+            (*guard).push(request);
+        }
+        self.input_queue.notify_all();
+        Ok(())
+    }
+
+    fn respawn_dead_workers(&mut self) {
+        if self.stop.load(Ordering::Relaxed) {
+            return;
+        }
+        let worker = match self.worker {
+            Some(ref worker) => worker,
+            None => return
+        };
+        for (handle, respawns) in self.thread_handles.iter_mut().zip(self.worker_respawns.iter_mut()) {
+            if !handle.is_finished() || *respawns > MAX_WORKER_RESPAWNS {
+                continue;
+            }
+            if *respawns == MAX_WORKER_RESPAWNS {
+                error!("Chunk loader thread died after {} respawns, giving up on it", MAX_WORKER_RESPAWNS);
+                *respawns += 1;
+                continue;
+            }
+            let dead_handle = std::mem::replace(handle, spawn_worker(worker.clone()));
+            if dead_handle.join().is_err() {
+                warn!("Chunk loader thread panicked");
+            }
+            warn!("Respawned chunk loader thread");
+            *respawns += 1;
+            self.respawn_count += 1;
+        }
+    }
+
+    pub fn get_queue_size(&self) -> usize {
         match self.input_queue.lock() {
-            Ok(mut guard) => {
-                for (pos, lod) in chunk_pos {
-                    if self.handeled_positions.insert(*pos) {
-                        (*guard).push_back((*pos, *lod));
-                    }
-                }
-                Ok(())
-            },
-            Err(_) => Err(ChunkError::MutexPoison)
+            Ok(guard) => (*guard).len(),
+            Err(_) => 0
         }
     }
 
+    pub fn get_upload_queue_size(&self) -> usize {
+        self.upload_queue.len()
+    }
+
     pub fn get_avg_build_time(&self) -> f64 {
         match self.build_stats.lock() {
             Ok(mut guard) => {
@@ -118,6 +291,15 @@ impl ChunkLoader {
     }
 }
 
+fn spawn_worker(worker: Worker) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        match worker.work() {
+            Ok(_) => trace!("Worker finished successfully"),
+            Err(e) =>  error!("Worker error: {}", e)
+        }
+    })
+}
+
 impl Drop for ChunkLoader {
     fn drop(&mut self) {
         self.stop();
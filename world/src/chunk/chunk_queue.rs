@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use glm::{dot, length, normalize, Vector2, Vector3};
+
+use super::ChunkRequest;
+use crate::ChunkPos;
+use core::Float;
+
+// chunks behind the focus count up to this factor farther away than chunks in view direction
+const BEHIND_PENALTY: Float = 2.;
+// minimal change of the focus before the queue gets reordered
+const CENTER_THRESHOLD: Float = 0.5;
+const DIRECTION_THRESHOLD: Float = 0.95;
+
+pub struct ChunkQueue {
+    requests: BTreeMap<ChunkPos, ChunkRequest>,
+    order: Vec<ChunkPos>,
+    center: Vector2<Float>,
+    direction: Vector2<Float>,
+    dirty: bool,
+}
+
+impl ChunkQueue {
+    pub fn push(&mut self, request: ChunkRequest) {
+        let pos = request.get_pos();
+        if self.requests.insert(pos, request).is_none() {
+            self.order.push(pos);
+            self.dirty = true;
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<ChunkRequest> {
+        if self.dirty {
+            self.reorder();
+        }
+        while let Some(pos) = self.order.pop() {
+            if let Some(request) = self.requests.remove(&pos) {
+                return Some(request);
+            }
+        }
+        None
+    }
+
+    pub fn cancel(&mut self, pos: ChunkPos) -> Option<ChunkRequest> {
+        self.requests.remove(&pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    // center in chunk units, direction in world coordinates
+    pub fn set_focus(&mut self, center: Vector2<Float>, direction: Vector3<Float>) {
+        let new_center = center;
+        let new_direction = match direction.truncate(2) {
+            d if length(d) > 1e-3 => normalize(d),
+            _ => self.direction,
+        };
+        if length(new_center - self.center) > CENTER_THRESHOLD
+            || dot(new_direction, self.direction) < DIRECTION_THRESHOLD
+        {
+            self.center = new_center;
+            self.direction = new_direction;
+            self.dirty = true;
+        }
+    }
+
+    fn reorder(&mut self) {
+        let mut order: Vec<(ChunkPos, Float)> = self
+            .requests
+            .keys()
+            .map(|pos| (*pos, self.calculate_priority(*pos)))
+            .collect();
+        // highest priority at the end, so it can get popped
+        order.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        self.order = order.into_iter().map(|(pos, _)| pos).collect();
+        self.dirty = false;
+    }
+
+    // lower value means more urgent
+    fn calculate_priority(&self, pos: ChunkPos) -> Float {
+        let offset = pos.get_center() - self.center;
+        let distance = length(offset);
+        if distance < 1e-3 {
+            return 0.;
+        }
+        let alignment = dot(offset / distance, self.direction);
+        distance * (1. + (BEHIND_PENALTY - 1.) * (1. - alignment) / 2.)
+    }
+}
+
+impl Default for ChunkQueue {
+    fn default() -> Self {
+        Self {
+            requests: BTreeMap::new(),
+            order: Vec::new(),
+            center: Vector2::new(0., 0.),
+            direction: Vector2::new(1., 0.),
+            dirty: false,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::HeightMap;
+use crate::ChunkPos;
+
+pub struct ChunkRequest {
+    pos: ChunkPos,
+    lod: u8,
+    height_map: Option<HeightMap>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ChunkRequest {
+    pub fn new(pos: ChunkPos, lod: u8) -> Self {
+        Self {
+            pos: pos,
+            lod: lod,
+            height_map: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_height_map(pos: ChunkPos, lod: u8, height_map: HeightMap) -> Self {
+        Self {
+            pos: pos,
+            lod: lod,
+            height_map: Some(height_map),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn get_pos(&self) -> ChunkPos {
+        self.pos
+    }
+
+    pub fn get_lod(&self) -> u8 {
+        self.lod
+    }
+
+    pub fn get_cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn take_height_map(&mut self) -> Option<HeightMap> {
+        self.height_map.take()
+    }
+}
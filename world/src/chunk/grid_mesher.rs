@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use gl::types::GLuint;
+use glm::{ Vector3, GenNum, cross, normalize };
+
+use core::Float;
+use core::graphics::mesh::{ VertexBuffer, SharedIndexBuffer };
+use crate::ChunkPos;
+use super::{ HeightMap, Architect };
+
+// writes the vertex buffers of a height map grid directly, every quad gets its own vertices
+// for the uv and texture layer, so the index buffer only depends on the grid size
+// and all chunks of a lod share the same one on the gpu
+pub struct GridMesher {
+    flat_shading: bool,
+    index_buffers: BTreeMap<i32, Arc<SharedIndexBuffer>>
+}
+
+impl GridMesher {
+    pub fn new(flat_shading: bool, cell_counts: &[i32]) -> Self {
+        let index_buffers = cell_counts.iter()
+            .map(|count| (*count, Arc::new(SharedIndexBuffer::new(create_index_buffer(*count, flat_shading)))))
+            .collect();
+        Self {
+            flat_shading: flat_shading,
+            index_buffers: index_buffers
+        }
+    }
+
+    pub fn create_buffer(&self, origin: ChunkPos, architect: &Architect, height_map: &HeightMap) -> VertexBuffer {
+        let cell_count = height_map.get_size() - 1;
+        let quad_vertex_count = if self.flat_shading { 6 } else { 4 };
+        let vertex_count = (cell_count * cell_count * quad_vertex_count) as usize;
+        let mut positions: Vec<Float> = Vec::with_capacity(3 * vertex_count);
+        let mut uvs: Vec<Float> = Vec::with_capacity(3 * vertex_count);
+        let mut normals: Vec<Float> = Vec::with_capacity(3 * vertex_count);
+
+        let vertex_normals = if self.flat_shading {
+            None
+        } else {
+            Some(architect.create_normals(origin, height_map))
+        };
+        let resolution = height_map.get_resolution();
+        for y in 0..cell_count {
+            for x in 0..cell_count {
+                let layer = architect.get_terrain(origin.get_sample_world_pos([x, y], resolution)).get_layer() as Float;
+                let corners = get_quad_corners(self.flat_shading);
+                let mut quad_buffer = [Vector3::from_s(0.); 6];
+                for (pos, corner) in quad_buffer.iter_mut().zip(corners.iter()) {
+                    let map_pos = [x + corner[0], y + corner[1]];
+                    *pos = Vector3::new((map_pos[0] * resolution) as Float,
+                                        (map_pos[1] * resolution) as Float,
+                                        height_map.get(&map_pos) as Float);
+                }
+                let quad_positions = &quad_buffer[..corners.len()];
+                for (corner, pos) in corners.iter().zip(quad_positions.iter()) {
+                    positions.extend(pos.as_array());
+                    uvs.extend(&[corner[0] as Float, corner[1] as Float, layer]);
+                }
+                match vertex_normals {
+                    Some(ref vertex_normals) => {
+                        for corner in corners.iter() {
+                            let index = (x + corner[0]) + (y + corner[1]) * height_map.get_size();
+                            normals.extend(vertex_normals[index as usize].as_array());
+                        }
+                    },
+                    None => {
+                        for triangle in quad_positions.chunks(3) {
+                            let normal = normalize(cross(triangle[1] - triangle[0], triangle[2] - triangle[0]));
+                            for _ in 0..3 {
+                                normals.extend(normal.as_array());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let index_buffer = match self.index_buffers.get(&cell_count) {
+            Some(index_buffer) => index_buffer.clone(),
+            None => Arc::new(SharedIndexBuffer::new(create_index_buffer(cell_count, self.flat_shading)))
+        };
+        let mut buffer = VertexBuffer::default();
+        buffer.add_float_buffer(positions, 0, 3);
+        buffer.add_float_buffer(uvs, 1, 3);
+        buffer.add_float_buffer(normals, 2, 3);
+        buffer.set_shared_index_buffer(index_buffer);
+        buffer
+    }
+}
+
+// quads are split along the diagonal from 0/0 to 1/1,
+// flat shaded quads need separate vertices per triangle
+fn get_quad_corners(flat_shading: bool) -> &'static [[i32; 2]] {
+    const SHARED_CORNERS: [[i32; 2]; 4] = [[0, 0], [1, 0], [0, 1], [1, 1]];
+    const TRIANGLE_CORNERS: [[i32; 2]; 6] = [[0, 0], [1, 1], [0, 1], [1, 1], [0, 0], [1, 0]];
+    if flat_shading {
+        &TRIANGLE_CORNERS
+    } else {
+        &SHARED_CORNERS
+    }
+}
+
+fn create_index_buffer(cell_count: i32, flat_shading: bool) -> Vec<GLuint> {
+    const SHARED_INDICES: [GLuint; 6] = [0, 3, 2, 3, 0, 1];
+    let quad_count = (cell_count * cell_count) as GLuint;
+    if flat_shading {
+        (0..6 * quad_count).collect()
+    } else {
+        (0..quad_count)
+            .flat_map(|quad| SHARED_INDICES.iter().map(move |i| 4 * quad + i))
+            .collect()
+    }
+}
@@ -1,42 +1,87 @@
-use std::cmp::Ordering;
+use std::mem::size_of;
+
+use crate::LocalPos;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HeightFormat {
+    Float,
+    // 16 bit multiples of a global step above the min height of the map
+    Quantized
+}
+
+enum HeightList {
+    Float(Vec<f32>),
+    Quantized { list: Vec<u16>, offset: f64, scale: f64 }
+}
 
 pub struct HeightMap {
     size: i32,
     resolution: i32,
-    height_list: Vec<f64>
+    height_list: HeightList,
+    min: f64,
+    max: f64
 }
 
 impl HeightMap {
 
+    #[allow(unused)]
     pub fn new(size: i32, resolution: i32) -> Self {
+        Self::from_fn(size, resolution, HeightFormat::Float, |_| 0.)
+    }
+
+    pub fn from_fn<F>(size: i32, resolution: i32, format: HeightFormat, mut height_fn: F) -> Self
+    where F: FnMut([i32; 2]) -> f64 {
         debug_assert!(size> 0);
         debug_assert!(resolution > 0);
-        let mut height_list =  Vec::new();
-        height_list.resize((size * size) as usize, 0.);
+        let mut heights = Vec::with_capacity((size * size) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                heights.push(height_fn([x, y]));
+            }
+        }
+        // the extremes are stored like every other height, so they don't need another scan
+        let (min, max) = find_min_max(heights.iter().cloned());
+        let (height_list, min, max) = match format {
+            HeightFormat::Float => {
+                let list = HeightList::Float(heights.iter().map(|h| *h as f32).collect());
+                (list, min as f32 as f64, max as f32 as f64)
+            },
+            HeightFormat::Quantized => {
+                let (offset, scale) = calculate_quantization(min, max);
+                let list = HeightList::Quantized {
+                    list: heights.iter().map(|h| quantize(*h, offset, scale)).collect(),
+                    offset: offset,
+                    scale: scale
+                };
+                (list, dequantize(quantize(min, offset, scale), offset, scale), dequantize(quantize(max, offset, scale), offset, scale))
+            }
+        };
         Self {
             size: size,
             resolution: resolution,
-            height_list: height_list
+            height_list: height_list,
+            min: min,
+            max: max
         }
     }
 
-    pub fn get_interpolated_height(&self, relative_pos: [f64; 2]) -> f64 {
-        let root_pos = [clamp((relative_pos[0].floor() as i32) / self.resolution, 0, self.size - 1),
-                        clamp((relative_pos[1].floor() as i32) / self.resolution, 0, self.size - 1)];
+    pub fn get_interpolated_height(&self, local_pos: LocalPos) -> f64 {
+        let map_pos = local_pos.get_map_pos(self.resolution);
+        let root_pos = [clamp(map_pos[0], 0, self.size - 1),
+                        clamp(map_pos[1], 0, self.size - 1)];
         let reference_height: [f64; 4] = [
             self.get(&root_pos),
             self.get(&[i32::min(root_pos[0] + 1, self.size - 1), root_pos[1]]),
             self.get(&[root_pos[0], i32::min(root_pos[1] + 1, self.size - 1)]),
             self.get(&[i32::min(root_pos[0] + 1, self.size - 1), i32::min(root_pos[1] + 1, self.size - 1)])
         ];
-        let relative_point = [relative_pos[0] - root_pos[0] as f64,
-                              relative_pos[1] - root_pos[1] as f64];
+        let relative_point = [local_pos.get_x() / self.resolution as f64 - root_pos[0] as f64,
+                              local_pos.get_y() / self.resolution as f64 - root_pos[1] as f64];
 
         let res = interpolate(relative_point, reference_height);
         res
     }
 
-    #[allow(unused)]
     pub fn get_size(&self) -> i32 {
         self.size
     }
@@ -45,53 +90,81 @@ impl HeightMap {
         self.resolution
     }
 
-    pub fn get_min(&self) -> f64 {
-        match self.height_list.iter().min_by(|a, b| {
-            if a > b {
-                Ordering::Greater
-            } else if a < b {
-                Ordering::Less
-            } else {
-                Ordering::Equal
-            }
-        }) {
-            Some(value) => *value,
-            None => unreachable!()
+    pub fn get_format(&self) -> HeightFormat {
+        match self.height_list {
+            HeightList::Float(_) => HeightFormat::Float,
+            HeightList::Quantized { .. } => HeightFormat::Quantized
         }
     }
 
-    pub fn get_max(&self) -> f64 {
-        match self.height_list.iter().max_by(|a, b| {
-            if a > b {
-                Ordering::Greater
-            } else if a < b {
-                Ordering::Less
-            } else {
-                Ordering::Equal
-            }
-        }) {
-            Some(value) => *value,
-            None => unreachable!()
+    pub fn get_memory_usage(&self) -> usize {
+        size_of::<Self>() + match self.height_list {
+            HeightList::Float(ref list) => list.len() * size_of::<f32>(),
+            HeightList::Quantized { ref list, .. } => list.len() * size_of::<u16>()
         }
     }
 
+    pub fn get_min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn get_max(&self) -> f64 {
+        self.max
+    }
+
+    #[allow(unused)]
     pub fn set(&mut self, pos: &[i32; 2], height: f64) {
         let index = self.calculate_index(pos);
-        self.height_list[index] = height;
+        self.set_by_index(index, height);
     }
 
     pub fn get(&self, pos: &[i32; 2]) -> f64 {
-        self.height_list[self.calculate_index(pos)]
+        self.get_by_index(self.calculate_index(pos))
     }
+
     #[allow(unused)]
     pub fn set_by_index(&mut self, index: usize, height: f64) {
-        self.height_list[index] = height;
+        let old_height = self.get_by_index(index);
+        if let HeightList::Quantized { offset, scale, .. } = self.height_list {
+            if height < offset || height > offset + scale * u16::max_value() as f64 {
+                self.requantize(f64::min(self.min, height), f64::max(self.max, height));
+            }
+        }
+        match self.height_list {
+            HeightList::Float(ref mut list) => list[index] = height as f32,
+            HeightList::Quantized { ref mut list, offset, scale } => list[index] = quantize(height, offset, scale)
+        }
+        let new_height = self.get_by_index(index);
+        if (old_height <= self.min && new_height > old_height) || (old_height >= self.max && new_height < old_height) {
+            self.update_min_max();      // an extreme got replaced
+        } else {
+            self.min = f64::min(self.min, new_height);
+            self.max = f64::max(self.max, new_height);
+        }
     }
 
-    #[allow(unused)]
     pub fn get_by_index(&self, index: usize) -> f64 {
-        debug_assert!(index < self.height_list.len());
-        self.height_list[index]
+        match self.height_list {
+            HeightList::Float(ref list) => list[index] as f64,
+            HeightList::Quantized { ref list, offset, scale } => dequantize(list[index], offset, scale)
+        }
+    }
+
+    fn requantize(&mut self, min: f64, max: f64) {
+        let heights: Vec<f64> = (0..(self.size * self.size) as usize).map(|i| self.get_by_index(i)).collect();
+        let (offset, scale) = calculate_quantization(min, max);
+        self.height_list = HeightList::Quantized {
+            list: heights.iter().map(|h| quantize(*h, offset, scale)).collect(),
+            offset: offset,
+            scale: scale
+        };
+        self.update_min_max();
+    }
+
+    fn update_min_max(&mut self) {
+        let (min, max) = find_min_max((0..(self.size * self.size) as usize).map(|i| self.get_by_index(i)));
+        self.min = min;
+        self.max = max;
     }
 
     #[allow(unused)]
@@ -124,3 +197,29 @@ fn clamp<T>(value: T, min: T, max: T) -> T
 where T: Ord {
     T::min(T::max(value, min), max)
 }
+
+fn find_min_max<I>(heights: I) -> (f64, f64)
+where I: Iterator<Item = f64> {
+    heights.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), h| (f64::min(min, h), f64::max(max, h)))
+}
+
+// the step and the offset snapped to it are the same for all chunks, so the shared border samples
+// of neighbours get the same heights, maps exceeding 16 bits of steps use a power of two multiple of it
+fn calculate_quantization(min: f64, max: f64) -> (f64, f64) {
+    const QUANTIZATION_STEP: f64 = 1. / 64.;
+    // the snapped offset can lie up to one step below the min
+    let max_steps = u16::max_value() as f64 - 1.;
+    let mut scale = QUANTIZATION_STEP;
+    while (max - min) / scale > max_steps {
+        scale *= 2.;
+    }
+    ((min / scale).floor() * scale, scale)
+}
+
+fn quantize(height: f64, offset: f64, scale: f64) -> u16 {
+    ((height - offset) / scale).round().max(0.).min(u16::max_value() as f64) as u16
+}
+
+fn dequantize(value: u16, offset: f64, scale: f64) -> f64 {
+    offset + value as f64 * scale
+}
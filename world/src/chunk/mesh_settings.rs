@@ -0,0 +1,37 @@
+use super::HeightFormat;
+
+#[derive(Clone)]
+pub struct MeshSettings {
+    flat_shading: bool,
+    height_format: HeightFormat,
+    max_vertical_errors: Option<[f64; 3]>
+}
+
+impl MeshSettings {
+    // without maximum errors per lod, the terrain is meshed as regular grid
+    pub fn new(flat_shading: bool, height_format: HeightFormat, max_vertical_errors: Option<[f64; 3]>) -> Self {
+        Self {
+            flat_shading: flat_shading,
+            height_format: height_format,
+            max_vertical_errors: max_vertical_errors
+        }
+    }
+
+    pub fn is_flat_shading(&self) -> bool {
+        self.flat_shading
+    }
+
+    pub fn get_height_format(&self) -> HeightFormat {
+        self.height_format
+    }
+
+    pub fn get_max_vertical_errors(&self) -> Option<[f64; 3]> {
+        self.max_vertical_errors
+    }
+}
+
+impl Default for MeshSettings {
+    fn default() -> Self {
+        Self::new(false, HeightFormat::Float, None)
+    }
+}
@@ -0,0 +1,190 @@
+use glm::{ Vector3, cross, dot, normalize };
+
+use core::{ Float, AABB };
+use super::HeightMap;
+
+// coarse height bounds of a height map, each level halves the cell count per side,
+// level 0 holds the bounds of single height map cells
+pub struct MinMaxTree {
+    levels: Vec<Level>,
+    resolution: Float
+}
+
+struct Level {
+    size: i32,
+    bounds: Vec<[f64; 2]>
+}
+
+impl MinMaxTree {
+    pub fn new(height_map: &HeightMap) -> Self {
+        let cell_count = height_map.get_size() - 1;
+        let mut levels = vec![create_cell_level(height_map)];
+        while levels.last().unwrap().size > 1 {
+            let next_level = create_parent_level(levels.last().unwrap());
+            levels.push(next_level);
+        }
+        debug_assert!(cell_count <= 1 << (levels.len() - 1));
+        Self {
+            levels: levels,
+            resolution: height_map.get_resolution() as Float
+        }
+    }
+
+    // origin is relative to the chunk, returns the distance along the direction and the surface normal
+    pub fn intersect_ray(
+        &self,
+        height_map: &HeightMap,
+        origin: Vector3<Float>,
+        direction: Vector3<Float>,
+        max_distance: Float) -> Option<(Float, Vector3<Float>)> {
+        let top_level = self.levels.len() - 1;
+        self.intersect_node(height_map, top_level, [0, 0], origin, direction, max_distance)
+    }
+
+    fn intersect_node(
+        &self,
+        height_map: &HeightMap,
+        level: usize,
+        node: [i32; 2],
+        origin: Vector3<Float>,
+        direction: Vector3<Float>,
+        max_distance: Float) -> Option<(Float, Vector3<Float>)> {
+        if level == 0 {
+            return intersect_cell(height_map, node, origin, direction)
+                .filter(|(distance, _)| *distance <= max_distance);
+        }
+        let child_level = &self.levels[level - 1];
+        let mut candidates: Vec<(Float, [i32; 2])> = Vec::with_capacity(4);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+            let child = [2 * node[0] + x, 2 * node[1] + y];
+            if child[0] >= child_level.size || child[1] >= child_level.size {
+                continue;
+            }
+            let bounds = self.get_node_box(level - 1, child);
+            if let Some(distance) = bounds.intersect_ray(origin, direction) {
+                if distance <= max_distance {
+                    candidates.push((distance, child));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut nearest: Option<(Float, Vector3<Float>)> = None;
+        for (entry_distance, child) in candidates {
+            let limit = match nearest {
+                Some((distance, _)) if distance <= entry_distance => break,
+                Some((distance, _)) => distance,
+                None => max_distance
+            };
+            if let Some(hit) = self.intersect_node(height_map, level - 1, child, origin, direction, limit) {
+                nearest = Some(hit);
+            }
+        }
+        nearest
+    }
+
+    fn get_node_box(&self, level: usize, node: [i32; 2]) -> AABB {
+        let level_ref = &self.levels[level];
+        let bounds = level_ref.bounds[(node[0] + level_ref.size * node[1]) as usize];
+        let extent = (1 << level) as Float * self.resolution;
+        let min = Vector3::new(node[0] as Float * extent, node[1] as Float * extent, bounds[0] as Float);
+        let max = Vector3::new(min.x + extent, min.y + extent, bounds[1] as Float);
+        AABB::new(min, max)
+    }
+}
+
+fn create_cell_level(height_map: &HeightMap) -> Level {
+    let size = height_map.get_size() - 1;
+    let mut bounds = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let heights = [
+                height_map.get(&[x, y]),
+                height_map.get(&[x + 1, y]),
+                height_map.get(&[x, y + 1]),
+                height_map.get(&[x + 1, y + 1])
+            ];
+            let min = heights.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = heights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            bounds.push([min, max]);
+        }
+    }
+    Level {
+        size: size,
+        bounds: bounds
+    }
+}
+
+fn create_parent_level(child: &Level) -> Level {
+    let size = (child.size + 1) / 2;
+    let mut bounds = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let mut node_bounds = [f64::INFINITY, f64::NEG_INFINITY];
+            for child_y in 2 * y..i32::min(2 * y + 2, child.size) {
+                for child_x in 2 * x..i32::min(2 * x + 2, child.size) {
+                    let child_bounds = child.bounds[(child_x + child.size * child_y) as usize];
+                    node_bounds[0] = f64::min(node_bounds[0], child_bounds[0]);
+                    node_bounds[1] = f64::max(node_bounds[1], child_bounds[1]);
+                }
+            }
+            bounds.push(node_bounds);
+        }
+    }
+    Level {
+        size: size,
+        bounds: bounds
+    }
+}
+
+// tests the two triangles of the cell, split the same way as the surface mesh
+fn intersect_cell(
+    height_map: &HeightMap,
+    cell: [i32; 2],
+    origin: Vector3<Float>,
+    direction: Vector3<Float>) -> Option<(Float, Vector3<Float>)> {
+    let resolution = height_map.get_resolution() as Float;
+    let get_vertex = |x: i32, y: i32| {
+        let map_pos = [cell[0] + x, cell[1] + y];
+        Vector3::new(map_pos[0] as Float * resolution,
+                     map_pos[1] as Float * resolution,
+                     height_map.get(&map_pos) as Float)
+    };
+    let triangles = [
+        [get_vertex(0, 0), get_vertex(1, 1), get_vertex(0, 1)],
+        [get_vertex(1, 1), get_vertex(0, 0), get_vertex(1, 0)]
+    ];
+    triangles.iter()
+        .filter_map(|t| intersect_triangle(t, origin, direction))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+fn intersect_triangle(
+    triangle: &[Vector3<Float>; 3],
+    origin: Vector3<Float>,
+    direction: Vector3<Float>) -> Option<(Float, Vector3<Float>)> {
+    const EPSILON: Float = 1e-6;
+    let edge_a = triangle[1] - triangle[0];
+    let edge_b = triangle[2] - triangle[0];
+    let p = cross(direction, edge_b);
+    let det = dot(edge_a, p);
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let inv_det = 1. / det;
+    let s = origin - triangle[0];
+    let u = dot(s, p) * inv_det;
+    if u < 0. || u > 1. {
+        return None;
+    }
+    let q = cross(s, edge_a);
+    let v = dot(direction, q) * inv_det;
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+    let distance = dot(edge_b, q) * inv_det;
+    if distance < 0. {
+        return None;
+    }
+    Some((distance, normalize(cross(edge_a, edge_b))))
+}
@@ -3,19 +3,51 @@ pub mod chunk_loader;
 pub mod chunk_error;
 pub mod chunk_size;
 pub mod height_map;
+pub mod chunk_cache;
+pub mod chunk_request;
+pub mod chunk_queue;
 mod chunk_builder;
 mod architect;
 mod worker;
+mod signaled;
+mod output_queue;
+mod upload_budget;
+mod build_result;
+mod retry_queue;
 mod build_stats;
+mod min_max_tree;
+mod mesh_settings;
+mod build_context;
+mod grid_mesher;
+mod adaptive_mesher;
+mod scatter;
+mod spawner;
+mod object_grid;
 
 pub use self::chunk::Chunk;
 pub use self::chunk_loader::ChunkLoader;
-pub use self::architect::Architect;
+pub use self::architect::{ Architect, calculate_normal };
 pub use self::chunk_size::CHUNK_SIZE;
 pub use self::chunk_error::ChunkError;
-#[allow(unused)]
-use self::chunk_size::{ get_world_pos, get_chunk_relative_pos };
+pub use self::chunk_cache::{ ChunkCache, CachedChunk };
+pub use self::chunk_request::ChunkRequest;
+pub use self::chunk_queue::ChunkQueue;
+use self::chunk_size::get_lod_resolution;
 use self::chunk_builder::ChunkBuilder;
 use self::worker::Worker;
+use self::signaled::Signaled;
+use self::output_queue::OutputQueue;
+pub use self::upload_budget::UploadBudget;
+use self::build_result::BuildResult;
+pub use self::retry_queue::RetryQueue;
 use self::build_stats::BuildStats;
-use self::height_map::HeightMap;
+pub use self::height_map::HeightMap;
+pub use self::height_map::HeightFormat;
+use self::min_max_tree::MinMaxTree;
+pub use self::mesh_settings::MeshSettings;
+use self::build_context::BuildContext;
+use self::grid_mesher::GridMesher;
+use self::adaptive_mesher::AdaptiveMesher;
+use self::scatter::Scatter;
+use self::spawner::Spawner;
+use self::object_grid::ObjectGrid;
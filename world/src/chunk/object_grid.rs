@@ -0,0 +1,64 @@
+use std::mem::size_of;
+
+use glm::Vector2;
+
+use core::Float;
+use super::CHUNK_SIZE;
+
+const CELL_SIZE: i32 = 8;
+const CELLS_PER_SIDE: i32 = CHUNK_SIZE / CELL_SIZE;
+
+// uniform grid over a chunk listing the objects, whose bounds overlap a cell, by their index.
+// Positions are relative to the chunk, objects reaching over the border are kept in the border cells,
+// so queries outside of the chunk still find them.
+pub struct ObjectGrid {
+    cells: Vec<Vec<usize>>
+}
+
+impl ObjectGrid {
+    pub fn insert(&mut self, index: usize, min: Vector2<Float>, max: Vector2<Float>) {
+        let (first, last) = (get_cell(min), get_cell(max));
+        for y in first[1]..last[1] + 1 {
+            for x in first[0]..last[0] + 1 {
+                self.cells[(x + y * CELLS_PER_SIDE) as usize].push(index);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.cells.iter_mut().for_each(|cell| cell.retain(|i| *i != index));
+    }
+
+    // indices of the objects in the cells overlapping the rectangle, sorted and without duplicates
+    pub fn query(&self, min: Vector2<Float>, max: Vector2<Float>) -> Vec<usize> {
+        let (first, last) = (get_cell(min), get_cell(max));
+        let mut indices = Vec::new();
+        for y in first[1]..last[1] + 1 {
+            for x in first[0]..last[0] + 1 {
+                indices.extend_from_slice(&self.cells[(x + y * CELLS_PER_SIDE) as usize]);
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    pub fn get_memory_usage(&self) -> usize {
+        self.cells.iter()
+            .map(|cell| size_of::<Vec<usize>>() + cell.capacity() * size_of::<usize>())
+            .sum()
+    }
+}
+
+fn get_cell(pos: Vector2<Float>) -> [i32; 2] {
+    let clamp = |value: Float| i32::min(i32::max((value / CELL_SIZE as Float).floor() as i32, 0), CELLS_PER_SIDE - 1);
+    [clamp(pos.x), clamp(pos.y)]
+}
+
+impl Default for ObjectGrid {
+    fn default() -> Self {
+        Self {
+            cells: vec![Vec::new(); (CELLS_PER_SIDE * CELLS_PER_SIDE) as usize]
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::BuildResult;
+
+pub struct OutputQueue {
+    results: Vec<BuildResult>,
+    capacity: usize,
+}
+
+impl OutputQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            results: Vec::new(),
+            capacity: capacity,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.results.len() >= self.capacity
+    }
+
+    pub fn push(&mut self, result: BuildResult) {
+        self.results.push(result);
+    }
+
+    // the oldest results, at most the count
+    pub fn drain(&mut self, count: usize) -> Vec<BuildResult> {
+        let count = usize::min(count, self.results.len());
+        self.results.drain(..count).collect()
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::ChunkPos;
+
+struct RetryState {
+    lod: u8,
+    attempts: u32,
+    due: Option<Instant>,
+}
+
+pub struct RetryQueue {
+    states: BTreeMap<ChunkPos, RetryState>,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl RetryQueue {
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            states: BTreeMap::new(),
+            max_retries: max_retries,
+            base_delay: base_delay,
+        }
+    }
+
+    // returns false, if the chunk exceeded the retry limit and should be dropped
+    pub fn schedule(&mut self, pos: ChunkPos, lod: u8) -> bool {
+        let base_delay = self.base_delay;
+        let state = self.states.entry(pos).or_insert(RetryState {
+            lod: lod,
+            attempts: 0,
+            due: None,
+        });
+        if state.attempts >= self.max_retries {
+            self.states.remove(&pos);
+            return false;
+        }
+        let delay = base_delay * 2u32.pow(u32::min(state.attempts, 16));
+        state.lod = lod;
+        state.attempts += 1;
+        state.due = Some(Instant::now() + delay);
+        true
+    }
+
+    pub fn take_due(&mut self) -> Vec<(ChunkPos, u8, u32)> {
+        let now = Instant::now();
+        let mut due_list = Vec::new();
+        for (pos, state) in self.states.iter_mut() {
+            match state.due {
+                Some(due) if due <= now => {
+                    state.due = None;
+                    due_list.push((*pos, state.lod, state.attempts));
+                }
+                _ => {}
+            }
+        }
+        due_list
+    }
+
+    pub fn remove(&mut self, pos: ChunkPos) {
+        self.states.remove(&pos);
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+}
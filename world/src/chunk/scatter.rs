@@ -0,0 +1,109 @@
+use rand::{ Rng, rngs::SmallRng, SeedableRng };
+
+use crate::{ ChunkPos, LocalPos, WorldPos };
+use super::CHUNK_SIZE;
+
+// candidates thrown per grid cell, more candidates fill the plane more densely
+const CANDIDATES_PER_CELL: usize = 4;
+
+// Poisson-disk scattering by prioritized dart throwing on a world aligned grid with the minimum
+// distance as cell size. Every cell gets random candidates seeded by the cell position and a
+// candidate survives, if no candidate with a higher priority lies within the minimum distance.
+// Since this only depends on the neighbouring cells, chunks agree on the points at their borders.
+pub struct Scatter {
+    min_distance: f64,
+    seed: u64
+}
+
+struct Candidate {
+    pos: WorldPos,
+    priority: u64
+}
+
+impl Scatter {
+    pub fn new(min_distance: f64, seed: u64) -> Self {
+        Self {
+            min_distance: min_distance,
+            seed: seed
+        }
+    }
+
+    // points within the chunk, the density maps a world position to the probability of keeping
+    // a candidate there, candidates are thinned out before checking the distances
+    pub fn scatter<F>(&self, chunk_pos: ChunkPos, density: F) -> Vec<LocalPos>
+    where F: Fn(WorldPos) -> f64 {
+        let origin = chunk_pos.get_world_pos();
+        let chunk_size = CHUNK_SIZE as f64;
+        let first_cell = [self.get_cell_index(origin.get_x()) - 1, self.get_cell_index(origin.get_y()) - 1];
+        let last_cell = [self.get_cell_index(origin.get_x() + chunk_size) + 1,
+                         self.get_cell_index(origin.get_y() + chunk_size) + 1];
+        let width = (last_cell[0] - first_cell[0] + 1) as usize;
+        let height = (last_cell[1] - first_cell[1] + 1) as usize;
+
+        let mut cells: Vec<Vec<Candidate>> = Vec::with_capacity(width * height);
+        for y in first_cell[1]..last_cell[1] + 1 {
+            for x in first_cell[0]..last_cell[0] + 1 {
+                cells.push(self.create_candidates([x, y], &density));
+            }
+        }
+
+        let min_distance_squared = self.min_distance * self.min_distance;
+        let mut points = Vec::new();
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                for candidate in cells[x + y * width].iter() {
+                    let pos = candidate.pos;
+                    // borders belong to the chunk with the larger coordinate, like world positions
+                    if pos.get_x() < origin.get_x() || pos.get_y() < origin.get_y() ||
+                       pos.get_x() >= origin.get_x() + chunk_size || pos.get_y() >= origin.get_y() + chunk_size {
+                        continue;
+                    }
+                    let suppressed = (y - 1..y + 2)
+                        .flat_map(|ny| (x - 1..x + 2).map(move |nx| nx + ny * width))
+                        .flat_map(|index| cells[index].iter())
+                        .any(|other| other.priority > candidate.priority &&
+                                     get_distance_squared(pos, other.pos) < min_distance_squared);
+                    if !suppressed {
+                        points.extend(pos.get_local_pos(chunk_pos));
+                    }
+                }
+            }
+        }
+        points
+    }
+
+    fn get_cell_index(&self, value: f64) -> i64 {
+        (value / self.min_distance).floor() as i64
+    }
+
+    // every candidate draws the same random numbers, so the thinning doesn't change the others
+    fn create_candidates<F>(&self, cell: [i64; 2], density: &F) -> Vec<Candidate>
+    where F: Fn(WorldPos) -> f64 {
+        let mut rng = SmallRng::seed_from_u64(self.get_cell_seed(cell));
+        let mut candidates = Vec::with_capacity(CANDIDATES_PER_CELL);
+        for _ in 0..CANDIDATES_PER_CELL {
+            let pos = WorldPos::new((cell[0] as f64 + rng.gen::<f64>()) * self.min_distance,
+                                    (cell[1] as f64 + rng.gen::<f64>()) * self.min_distance);
+            let priority = rng.gen::<u64>();
+            if rng.gen::<f64>() < density(pos) {
+                candidates.push(Candidate {
+                    pos: pos,
+                    priority: priority
+                });
+            }
+        }
+        candidates
+    }
+
+    fn get_cell_seed(&self, cell: [i64; 2]) -> u64 {
+        let mut hash = self.seed ^ (cell[0] as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        hash = (hash ^ (cell[1] as u64)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        hash ^ (hash >> 31)
+    }
+}
+
+fn get_distance_squared(a: WorldPos, b: WorldPos) -> f64 {
+    let diff_x = a.get_x() - b.get_x();
+    let diff_y = a.get_y() - b.get_y();
+    diff_x * diff_x + diff_y * diff_y
+}
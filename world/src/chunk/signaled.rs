@@ -0,0 +1,40 @@
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+use super::ChunkError;
+
+pub struct Signaled<T> {
+    value: Mutex<T>,
+    condvar: Condvar,
+}
+
+impl<T> Signaled<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Mutex::new(value),
+            condvar: Condvar::new(),
+        }
+    }
+
+    pub fn lock(&self) -> Result<MutexGuard<'_, T>, ChunkError> {
+        match self.value.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_poisoned) => Err(ChunkError::MutexPoison),
+        }
+    }
+
+    // blocks until notified and the condition doesn't hold anymore
+    pub fn wait_while<F>(&self, condition: F) -> Result<MutexGuard<'_, T>, ChunkError>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let guard = self.lock()?;
+        match self.condvar.wait_while(guard, condition) {
+            Ok(guard) => Ok(guard),
+            Err(_poisoned) => Err(ChunkError::MutexPoison),
+        }
+    }
+
+    pub fn notify_all(&self) {
+        self.condvar.notify_all();
+    }
+}
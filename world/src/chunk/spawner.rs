@@ -0,0 +1,126 @@
+use rand::Rng;
+use glm::{ Vector3, GenNum };
+
+use core::{ Float, Object, ObjectManager };
+use core::object::{ ObjectError, SpawnRule };
+use core::traits::{ Translatable, Rotatable, Scalable };
+use crate::{ ChunkPos, TerrainType, WorldPos };
+use super::{ Architect, HeightMap, Scatter };
+
+// places the objects of one prototype by its spawn rule,
+// positions are scattered with the minimum distance of the rule and thinned out by the rule's density
+pub struct Spawner {
+    prototype_name: String,
+    rule: SpawnRule,
+    // none for any terrain, a rule with only unknown terrain names spawns nothing
+    terrain_types: Option<Vec<TerrainType>>,
+    scatter: Scatter
+}
+
+impl Spawner {
+    pub fn new(prototype_name: &str, rule: SpawnRule, seed: u64) -> Self {
+        let terrain_names = rule.get_terrain_names();
+        let terrain_types = if terrain_names.is_empty() {
+            None
+        } else {
+            let types: Vec<TerrainType> = terrain_names.iter()
+                .filter_map(|name| match TerrainType::from_name(name) {
+                    Some(terrain_type) => Some(terrain_type),
+                    None => {
+                        warn!("Unknown terrain type '{}' in spawn rule of '{}'", name, prototype_name);
+                        None
+                    }
+                })
+                .collect();
+            if types.is_empty() {
+                warn!("No known terrain type in spawn rule of '{}', it won't be spawned", prototype_name);
+            }
+            Some(types)
+        };
+        // every prototype gets its own pattern
+        let name_hash = prototype_name.bytes()
+            .fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3));
+        let scatter = Scatter::new(f64::max(rule.get_min_distance(), 0.5), seed ^ name_hash);
+        Self {
+            prototype_name: prototype_name.to_string(),
+            rule: rule,
+            terrain_types: terrain_types,
+            scatter: scatter
+        }
+    }
+
+    // objects are positioned relative to the chunk
+    pub fn spawn<R: Rng + ?Sized>(
+        &self,
+        chunk_pos: ChunkPos,
+        height_map: &HeightMap,
+        architect: &Architect,
+        object_manager: &ObjectManager,
+        rng: &mut R) -> Result<Vec<Object>, ObjectError> {
+        let positions = self.scatter.scatter(chunk_pos, |world_pos| self.get_density(architect, world_pos));
+        let mut objects = Vec::with_capacity(positions.len());
+        for local_pos in positions.into_iter() {
+            let height = height_map.get_interpolated_height(local_pos);
+            let mut object = object_manager.create_object(&self.prototype_name)?;
+            object.set_translation(Vector3::new(local_pos.get_x() as Float, local_pos.get_y() as Float, height as Float));
+
+            let scale = gen_in_range(rng, self.rule.get_scale_range());
+            let height_scale = gen_in_range(rng, self.rule.get_height_scale_range());
+            object.set_scale(Vector3::new(scale, scale, scale * height_scale));
+
+            let mut rotation = Vector3::new(
+                gen_in_range(rng, self.rule.get_tilt_range()),
+                gen_in_range(rng, self.rule.get_tilt_range()),
+                gen_in_range(rng, self.rule.get_rotation_range()));
+            if self.rule.is_aligned_to_normal() {
+                rotation = rotation + get_alignment(architect.get_normal(local_pos.to_world_pos(chunk_pos)));
+            }
+            object.set_rotation(rotation);
+            objects.push(object);
+        }
+        Ok(objects)
+    }
+
+    // cheap checks first, the slope needs several height samples
+    fn get_density(&self, architect: &Architect, world_pos: WorldPos) -> f64 {
+        if let Some(ref terrain_types) = self.terrain_types {
+            if !terrain_types.contains(&architect.get_terrain(world_pos).get_type()) {
+                return 0.;
+            }
+        }
+        let altitude = self.rule.get_altitude_range();
+        let height = architect.get_height(world_pos);
+        if height < altitude[0] || height > altitude[1] {
+            return 0.;
+        }
+        let slope_range = self.rule.get_slope_range();
+        if slope_range[0] > 0. || slope_range[1] < 90. {
+            let normal = architect.get_normal(world_pos);
+            let slope = (normal.z as f64).acos().to_degrees();
+            if slope < slope_range[0] || slope > slope_range[1] {
+                return 0.;
+            }
+        }
+        if self.rule.follows_vegetation() {
+            self.rule.get_density() * architect.get_vegetation_density(world_pos)
+        } else {
+            self.rule.get_density()
+        }
+    }
+}
+
+fn gen_in_range<R: Rng + ?Sized>(rng: &mut R, range: [Float; 2]) -> Float {
+    if range[0] < range[1] {
+        rng.gen_range(range[0], range[1])
+    } else {
+        range[0]
+    }
+}
+
+// rotation around x and y, which turns the up axis onto the normal
+fn get_alignment(normal: Vector3<Float>) -> Vector3<Float> {
+    let mut alignment = Vector3::from_s(0.);
+    alignment.x = (-normal.y).atan2(normal.z);
+    alignment.y = normal.x.max(-1.).min(1.).asin();
+    alignment
+}
@@ -0,0 +1,23 @@
+use std::time::{Duration, Instant};
+
+// a budget of zero means no limit for that value
+pub struct UploadBudget {
+    time: Duration,
+    bytes: usize,
+}
+
+impl UploadBudget {
+    pub fn new(time_ms: f32, kilobytes: u32) -> Self {
+        Self {
+            time: Duration::from_micros((f32::max(time_ms, 0.) * 1000.) as u64),
+            bytes: kilobytes as usize * 1024,
+        }
+    }
+
+    pub fn is_exhausted(&self, upload_start: &Instant, uploaded_bytes: usize) -> bool {
+        let time_exhausted =
+            self.time > Duration::from_millis(0) && upload_start.elapsed() >= self.time;
+        let bytes_exhausted = self.bytes > 0 && uploaded_bytes >= self.bytes;
+        time_exhausted || bytes_exhausted
+    }
+}
@@ -1,80 +1,82 @@
-use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use super::{Architect, BuildStats, ChunkBuilder, ChunkError};
-use core::ObjectManager;
+use super::{
+    BuildContext, BuildResult, BuildStats, ChunkBuilder, ChunkError, ChunkQueue, ChunkRequest,
+    OutputQueue, Signaled,
+};
 
 #[derive(Clone)]
 pub struct Worker {
-    architect: Arc<Architect>,
-    object_manager: Arc<ObjectManager>,
+    context: Arc<BuildContext>,
     stop: Arc<AtomicBool>,
-    input_queue: Arc<Mutex<VecDeque<([i32; 2], u8)>>>,
-    output_queue: Arc<Mutex<Vec<ChunkBuilder>>>,
+    input_queue: Arc<Signaled<ChunkQueue>>,
+    output_queue: Arc<Signaled<OutputQueue>>,
     build_stats: Arc<Mutex<BuildStats>>,
-    random_state: [u8; 16],
 }
 
 impl Worker {
     pub fn new(
-        architect: Arc<Architect>,
-        object_manager: Arc<ObjectManager>,
+        context: Arc<BuildContext>,
         stop: Arc<AtomicBool>,
-        input_queue: Arc<Mutex<VecDeque<([i32; 2], u8)>>>,
-        output_queue: Arc<Mutex<Vec<ChunkBuilder>>>,
+        input_queue: Arc<Signaled<ChunkQueue>>,
+        output_queue: Arc<Signaled<OutputQueue>>,
         build_stats: Arc<Mutex<BuildStats>>,
-        random_state: [u8; 16],
     ) -> Worker {
         Worker {
-            architect: architect,
-            object_manager: object_manager,
+            context: context,
             stop: stop,
             input_queue: input_queue,
             output_queue: output_queue,
             build_stats: build_stats,
-            random_state: random_state,
         }
     }
 
     pub fn work(&self) -> Result<(), ChunkError> {
-        while !self.stop.load(Ordering::Relaxed) {
-            self.work_cycle()?;
-        }
-        Ok(())
-    }
-
-    fn work_cycle(&self) -> Result<(), ChunkError> {
-        let sleep_time = Duration::from_millis(500);
-        if let Some((pos, lod)) = self.get_chunk_pos()? {
+        while let Some(request) = self.wait_for_request()? {
+            let lod = request.get_lod();
             let build_start = Instant::now();
-            self.build_chunk(pos, lod)?;
+            self.build_chunk(request)?;
             if lod == 0 {
                 // only want stats for high quality chunks
                 self.handle_build_stats(&build_start)?;
             }
-        } else {
-            thread::sleep(sleep_time);
         }
         Ok(())
     }
 
-    fn build_chunk(&self, chunk_pos: [i32; 2], lod: u8) -> Result<(), ChunkError> {
-        let builder = ChunkBuilder::new(
-            chunk_pos,
-            lod,
-            &self.architect,
-            &self.object_manager,
-            &self.random_state,
-        )?;
+    fn build_chunk(&self, request: ChunkRequest) -> Result<(), ChunkError> {
+        if request.is_cancelled() {
+            return Ok(());
+        }
+        let pos = request.get_pos();
+        let lod = request.get_lod();
+        // a panicking build must not take down the worker
+        let build = panic::catch_unwind(AssertUnwindSafe(|| {
+            ChunkBuilder::new(request, &self.context)
+        }));
+        let result = match build {
+            Ok(Ok(builder)) if builder.is_cancelled() => return Ok(()),
+            Ok(Ok(builder)) => BuildResult::Finished(builder),
+            Ok(Err(error)) => BuildResult::Failed {
+                pos: pos,
+                lod: lod,
+                error: error,
+            },
+            Err(_) => BuildResult::Failed {
+                pos: pos,
+                lod: lod,
+                error: ChunkError::BuildPanic(pos),
+            },
+        };
 
-        match self.output_queue.lock() {
-            Ok(mut guard) => (*guard).push(builder),
-            Err(_poisoned) => {
-                return Err(ChunkError::MutexPoison);
-            }
+        let mut guard = self
+            .output_queue
+            .wait_while(|output| output.is_full() && !self.should_stop())?;
+        if !self.should_stop() {
+            (*guard).push(result);
         }
         Ok(())
     }
@@ -91,10 +93,19 @@ impl Worker {
         Ok(())
     }
 
-    fn get_chunk_pos(&self) -> Result<Option<([i32; 2], u8)>, ChunkError> {
-        match self.input_queue.lock() {
-            Ok(mut guard) => Ok((*guard).pop_back()),
-            Err(_poisoned) => Err(ChunkError::MutexPoison),
+    // blocks until a request is available, returns none if the worker should stop
+    fn wait_for_request(&self) -> Result<Option<ChunkRequest>, ChunkError> {
+        let mut guard = self
+            .input_queue
+            .wait_while(|queue| queue.len() == 0 && !self.should_stop())?;
+        if self.should_stop() {
+            Ok(None)
+        } else {
+            Ok((*guard).pop())
         }
     }
+
+    fn should_stop(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
 }
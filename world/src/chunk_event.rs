@@ -0,0 +1,35 @@
+use crate::{ ChunkHeights, ChunkPos };
+
+#[derive(Debug)]
+pub enum ChunkEvent<'a> {
+    Loaded { pos: ChunkPos, lod: u8, heights: ChunkHeights<'a> },
+    LodChanged { pos: ChunkPos, previous_lod: u8, lod: u8, heights: ChunkHeights<'a> },
+    // emitted before the chunk is removed
+    Unloaded { pos: ChunkPos, lod: u8, heights: ChunkHeights<'a> }
+}
+
+impl<'a> ChunkEvent<'a> {
+    pub fn get_pos(&self) -> ChunkPos {
+        match self {
+            ChunkEvent::Loaded { pos, .. } => *pos,
+            ChunkEvent::LodChanged { pos, .. } => *pos,
+            ChunkEvent::Unloaded { pos, .. } => *pos
+        }
+    }
+
+    pub fn get_lod(&self) -> u8 {
+        match self {
+            ChunkEvent::Loaded { lod, .. } => *lod,
+            ChunkEvent::LodChanged { lod, .. } => *lod,
+            ChunkEvent::Unloaded { lod, .. } => *lod
+        }
+    }
+
+    pub fn get_heights(&self) -> &ChunkHeights<'a> {
+        match self {
+            ChunkEvent::Loaded { heights, .. } => heights,
+            ChunkEvent::LodChanged { heights, .. } => heights,
+            ChunkEvent::Unloaded { heights, .. } => heights
+        }
+    }
+}
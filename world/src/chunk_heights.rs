@@ -0,0 +1,63 @@
+use std::fmt;
+
+use crate::chunk::HeightMap;
+use crate::{ ChunkPos, WorldPos };
+
+// read-only access to the height data of a loaded chunk
+pub struct ChunkHeights<'a> {
+    chunk_pos: ChunkPos,
+    height_map: &'a HeightMap
+}
+
+impl<'a> ChunkHeights<'a> {
+    pub fn new(chunk_pos: ChunkPos, height_map: &'a HeightMap) -> Self {
+        Self {
+            chunk_pos: chunk_pos,
+            height_map: height_map
+        }
+    }
+
+    // world position of the height map origin
+    pub fn get_origin(&self) -> WorldPos {
+        self.chunk_pos.get_world_pos()
+    }
+
+    // sample count per side
+    pub fn get_size(&self) -> i32 {
+        self.height_map.get_size()
+    }
+
+    // world units between samples
+    pub fn get_resolution(&self) -> i32 {
+        self.height_map.get_resolution()
+    }
+
+    pub fn get_min(&self) -> f64 {
+        self.height_map.get_min()
+    }
+
+    pub fn get_max(&self) -> f64 {
+        self.height_map.get_max()
+    }
+
+    pub fn get_sample(&self, map_pos: [i32; 2]) -> f64 {
+        self.height_map.get(&map_pos)
+    }
+
+    // interpolated height at a world position, none outside of the chunk
+    pub fn get_height(&self, world_pos: WorldPos) -> Option<f64> {
+        let local_pos = world_pos.get_local_pos(self.chunk_pos)?;
+        Some(self.height_map.get_interpolated_height(local_pos))
+    }
+}
+
+// the samples are left out, they would flood the output
+impl<'a> fmt::Debug for ChunkHeights<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChunkHeights")
+            .field("chunk_pos", &self.chunk_pos)
+            .field("size", &self.get_size())
+            .field("resolution", &self.get_resolution())
+            .finish()
+    }
+}
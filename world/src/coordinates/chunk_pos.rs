@@ -0,0 +1,114 @@
+use std::fmt;
+use std::ops::{ Add, Sub };
+
+use glm::Vector2;
+
+use core::{ Float, distance::get_distance_2d };
+use crate::chunk::CHUNK_SIZE;
+use super::WorldPos;
+
+// position of a chunk in chunk units, chunk (x, y) covers the world positions
+// from x * CHUNK_SIZE up to, excluding, (x + 1) * CHUNK_SIZE
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ChunkPos {
+    x: i32,
+    y: i32
+}
+
+impl ChunkPos {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            x: x,
+            y: y
+        }
+    }
+
+    pub fn get_x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn get_y(&self) -> i32 {
+        self.y
+    }
+
+    pub fn to_array(&self) -> [i32; 2] {
+        [self.x, self.y]
+    }
+
+    pub fn offset(&self, x: i32, y: i32) -> Self {
+        Self::new(self.x + x, self.y + y)
+    }
+
+    // world position of the chunk corner with the lowest coordinates
+    pub fn get_world_pos(&self) -> WorldPos {
+        WorldPos::new(self.x as f64 * CHUNK_SIZE as f64, self.y as f64 * CHUNK_SIZE as f64)
+    }
+
+    // world position of a height map sample, which may lie beyond the chunk borders
+    pub fn get_sample_world_pos(&self, map_pos: [i32; 2], resolution: i32) -> WorldPos {
+        self.get_world_pos().offset((map_pos[0] * resolution) as f64, (map_pos[1] * resolution) as f64)
+    }
+
+    // chunk center in chunk units
+    pub fn get_center(&self) -> Vector2<Float> {
+        Vector2::new(self.x as Float + 0.5, self.y as Float + 0.5)
+    }
+
+    // distance in chunk units
+    pub fn get_distance(&self, other: ChunkPos) -> f64 {
+        get_distance_2d(self.to_array(), other.to_array())
+    }
+}
+
+impl Add for ChunkPos {
+    type Output = ChunkPos;
+
+    fn add(self, other: ChunkPos) -> ChunkPos {
+        ChunkPos::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for ChunkPos {
+    type Output = ChunkPos;
+
+    fn sub(self, other: ChunkPos) -> ChunkPos {
+        ChunkPos::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl fmt::Display for ChunkPos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_pos_of_chunk() {
+        assert_eq!(ChunkPos::new(0, 0).get_world_pos(), WorldPos::new(0., 0.));
+        assert_eq!(ChunkPos::new(-1, 2).get_world_pos(), WorldPos::new(-64., 128.));
+        assert_eq!(ChunkPos::new(i32::min_value(), i32::max_value()).get_world_pos(),
+                   WorldPos::new(i32::min_value() as f64 * 64., i32::max_value() as f64 * 64.));
+    }
+
+    #[test]
+    fn sample_world_pos() {
+        let pos = ChunkPos::new(-1, 1);
+        assert_eq!(pos.get_sample_world_pos([0, 0], 8), WorldPos::new(-64., 64.));
+        assert_eq!(pos.get_sample_world_pos([8, 8], 8), WorldPos::new(0., 128.));
+        assert_eq!(pos.get_sample_world_pos([-1, 65], 1), WorldPos::new(-65., 129.));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = ChunkPos::new(-3, 4);
+        let b = ChunkPos::new(5, -6);
+        assert_eq!(a + b, ChunkPos::new(2, -2));
+        assert_eq!(a - b, ChunkPos::new(-8, 10));
+        assert_eq!(a.offset(3, -4), ChunkPos::new(0, 0));
+        assert_eq!(ChunkPos::new(-3, 0).get_distance(ChunkPos::new(0, -4)), 5.);
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use crate::chunk::CHUNK_SIZE;
+use super::{ ChunkPos, WorldPos };
+
+// position relative to the lowest corner of a chunk, both borders included
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LocalPos {
+    x: f64,
+    y: f64
+}
+
+impl LocalPos {
+    // none, if the position lies outside of the chunk
+    pub fn new(x: f64, y: f64) -> Option<Self> {
+        if is_local(x) && is_local(y) {
+            Some(Self {
+                x: x,
+                y: y
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn get_y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn to_array(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    pub fn to_world_pos(&self, chunk_pos: ChunkPos) -> WorldPos {
+        chunk_pos.get_world_pos().offset(self.x, self.y)
+    }
+
+    // height map sample at or below the position, for a map with the given resolution
+    pub fn get_map_pos(&self, resolution: i32) -> [i32; 2] {
+        [(self.x / resolution as f64).floor() as i32, (self.y / resolution as f64).floor() as i32]
+    }
+}
+
+impl fmt::Display for LocalPos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2}/{:.2}", self.x, self.y)
+    }
+}
+
+fn is_local(value: f64) -> bool {
+    value >= 0. && value <= CHUNK_SIZE as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert!(LocalPos::new(0., 0.).is_some());
+        assert!(LocalPos::new(64., 64.).is_some());
+        assert!(LocalPos::new(-0.01, 10.).is_none());
+        assert!(LocalPos::new(10., 64.01).is_none());
+        assert!(LocalPos::new(std::f64::NAN, 0.).is_none());
+    }
+
+    #[test]
+    fn map_pos() {
+        let pos = LocalPos::new(63.5, 8.).unwrap();
+        assert_eq!(pos.get_map_pos(1), [63, 8]);
+        assert_eq!(pos.get_map_pos(8), [7, 1]);
+        assert_eq!(LocalPos::new(64., 0.).unwrap().get_map_pos(8), [8, 0]);
+    }
+
+    #[test]
+    fn world_pos_round_trip() {
+        let chunk_pos = ChunkPos::new(-2, -1);
+        let pos = LocalPos::new(0.25, 63.75).unwrap();
+        let world_pos = pos.to_world_pos(chunk_pos);
+        assert_eq!(world_pos, WorldPos::new(-127.75, -0.25));
+        assert_eq!(world_pos.get_local_pos(chunk_pos), Some(pos));
+    }
+}
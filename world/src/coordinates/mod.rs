@@ -0,0 +1,7 @@
+pub mod world_pos;
+pub mod chunk_pos;
+pub mod local_pos;
+
+pub use self::world_pos::WorldPos;
+pub use self::chunk_pos::ChunkPos;
+pub use self::local_pos::LocalPos;
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use glm::{ Vector2, Vector3 };
+
+use core::Float;
+use crate::chunk::CHUNK_SIZE;
+use super::{ ChunkPos, LocalPos };
+
+// absolute position on the terrain plane, kept in f64 to stay exact far away from the spawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WorldPos {
+    x: f64,
+    y: f64
+}
+
+impl WorldPos {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self {
+            x: x,
+            y: y
+        }
+    }
+
+    // position given relative to the floating origin chunk
+    pub fn from_relative(pos: Vector3<Float>, origin: ChunkPos) -> Self {
+        origin.get_world_pos().offset(pos.x as f64, pos.y as f64)
+    }
+
+    pub fn get_x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn get_y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn to_array(&self) -> [f64; 2] {
+        [self.x, self.y]
+    }
+
+    pub fn offset(&self, x: f64, y: f64) -> Self {
+        Self::new(self.x + x, self.y + y)
+    }
+
+    // position relative to the floating origin chunk
+    pub fn to_relative(&self, origin: ChunkPos) -> Vector2<Float> {
+        let origin = origin.get_world_pos();
+        Vector2::new((self.x - origin.x) as Float, (self.y - origin.y) as Float)
+    }
+
+    // chunk containing the position, chunk borders belong to the chunk with the larger coordinate,
+    // so -64 lies in chunk -1 and -64.5 in chunk -2
+    // none for positions which are not finite or beyond the chunk range
+    pub fn get_chunk_pos(&self) -> Option<ChunkPos> {
+        let x = to_chunk_coordinate(self.x)?;
+        let y = to_chunk_coordinate(self.y)?;
+        Some(ChunkPos::new(x, y))
+    }
+
+    // none, if the position does not lie within the chunk
+    pub fn get_local_pos(&self, chunk_pos: ChunkPos) -> Option<LocalPos> {
+        let origin = chunk_pos.get_world_pos();
+        LocalPos::new(self.x - origin.x, self.y - origin.y)
+    }
+}
+
+impl fmt::Display for WorldPos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2}/{:.2}", self.x, self.y)
+    }
+}
+
+fn to_chunk_coordinate(value: f64) -> Option<i32> {
+    let chunk_value = (value / CHUNK_SIZE as f64).floor();
+    if chunk_value.is_finite() && chunk_value >= i32::min_value() as f64 && chunk_value <= i32::max_value() as f64 {
+        Some(chunk_value as i32)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_of(x: f64, y: f64) -> ChunkPos {
+        WorldPos::new(x, y).get_chunk_pos().unwrap()
+    }
+
+    #[test]
+    fn chunk_pos_of_positive_positions() {
+        assert_eq!(chunk_of(0., 0.), ChunkPos::new(0, 0));
+        assert_eq!(chunk_of(63.9, 0.5), ChunkPos::new(0, 0));
+        assert_eq!(chunk_of(64., 127.99), ChunkPos::new(1, 1));
+        assert_eq!(chunk_of(128., 6400.), ChunkPos::new(2, 100));
+    }
+
+    #[test]
+    fn chunk_pos_of_negative_positions() {
+        assert_eq!(chunk_of(-0.01, -1.), ChunkPos::new(-1, -1));
+        assert_eq!(chunk_of(-63.9, -32.), ChunkPos::new(-1, -1));
+        assert_eq!(chunk_of(-64., -64.), ChunkPos::new(-1, -1));
+        assert_eq!(chunk_of(-64.01, -65.), ChunkPos::new(-2, -2));
+        assert_eq!(chunk_of(-128., -129.), ChunkPos::new(-2, -3));
+    }
+
+    #[test]
+    fn chunk_pos_of_invalid_positions() {
+        assert_eq!(WorldPos::new(std::f64::NAN, 0.).get_chunk_pos(), None);
+        assert_eq!(WorldPos::new(0., std::f64::INFINITY).get_chunk_pos(), None);
+        assert_eq!(WorldPos::new(1e12, 0.).get_chunk_pos(), None);
+        assert_eq!(WorldPos::new(0., -1e12).get_chunk_pos(), None);
+    }
+
+    #[test]
+    fn chunk_pos_round_trip() {
+        for &pos in &[ChunkPos::new(0, 0), ChunkPos::new(-1, 3), ChunkPos::new(-1000, -7), ChunkPos::new(1 << 20, -(1 << 20))] {
+            assert_eq!(pos.get_world_pos().get_chunk_pos(), Some(pos));
+            assert_eq!(pos.get_world_pos().offset(-0.5, -0.5).get_chunk_pos(), Some(pos.offset(-1, -1)));
+        }
+    }
+
+    #[test]
+    fn local_pos_of_world_pos() {
+        let local = WorldPos::new(-64., -1.).get_local_pos(ChunkPos::new(-1, -1)).unwrap();
+        assert_eq!(local.to_array(), [0., 63.]);
+        assert!(WorldPos::new(-64.5, -1.).get_local_pos(ChunkPos::new(-1, -1)).is_none());
+        // the far border is shared with the neighbour and still local to the chunk
+        assert!(WorldPos::new(0., -1.).get_local_pos(ChunkPos::new(-1, -1)).is_some());
+    }
+
+    #[test]
+    fn relative_positions() {
+        let origin = ChunkPos::new(-2, 5);
+        let pos = WorldPos::from_relative(Vector3::new(-10., 20., 3.), origin);
+        assert_eq!(pos, WorldPos::new(-138., 340.));
+        assert_eq!(pos.to_relative(origin), Vector2::new(-10., 20.));
+    }
+}
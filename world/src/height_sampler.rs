@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+use glm::Vector3;
+
+use crate::chunk::{calculate_normal, Architect};
+use crate::WorldPos;
+use core::Float;
+
+// samples are taken on a grid of this many steps per world unit
+const SAMPLE_STEPS: f64 = 64.;
+
+pub struct HeightSampler {
+    architect: Arc<Architect>,
+    cache: RefCell<SampleCache>,
+}
+
+struct SampleCache {
+    heights: BTreeMap<[i64; 2], f64>,
+    insert_order: VecDeque<[i64; 2]>,
+    capacity: usize,
+}
+
+impl HeightSampler {
+    pub fn new(architect: Arc<Architect>, cache_capacity: usize) -> Self {
+        Self {
+            architect: architect,
+            cache: RefCell::new(SampleCache {
+                heights: BTreeMap::new(),
+                insert_order: VecDeque::new(),
+                capacity: cache_capacity,
+            }),
+        }
+    }
+
+    pub fn sample_height(&self, world_pos: WorldPos) -> f64 {
+        let key = [
+            (world_pos.get_x() * SAMPLE_STEPS).round() as i64,
+            (world_pos.get_y() * SAMPLE_STEPS).round() as i64,
+        ];
+        if let Some(height) = self.cache.borrow().heights.get(&key) {
+            return *height;
+        }
+        let height = self.architect.get_height(WorldPos::new(
+            key[0] as f64 / SAMPLE_STEPS,
+            key[1] as f64 / SAMPLE_STEPS,
+        ));
+        self.cache.borrow_mut().insert(key, height);
+        height
+    }
+
+    pub fn sample_normal(&self, world_pos: WorldPos) -> Vector3<Float> {
+        calculate_normal(world_pos, |pos| self.sample_height(pos))
+    }
+}
+
+impl SampleCache {
+    fn insert(&mut self, key: [i64; 2], height: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.insert_order.len() >= self.capacity {
+            if let Some(oldest) = self.insert_order.pop_front() {
+                self.heights.remove(&oldest);
+            }
+        }
+        self.heights.insert(key, height);
+        self.insert_order.push_back(key);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use glm::{ Matrix4, Vector3, length };
+
+use core::graphics::{ GraphicsError, Material, Mesh };
+use core::object::{ ObjectPrototype, PrototypeData };
+use core::traits::RenderInfo;
+use core::{ Camera, Float, Frustum, Object };
+
+// model matrices of the visible objects grouped by their prototype and lod, so every prototype
+// is drawn by a single instanced draw call per lod and material, the buffers are kept between frames.
+// Objects outside of the view frustum are culled by their bounding spheres.
+pub struct InstanceBatch {
+    batches: BTreeMap<(usize, u8), Batch>,
+    view_pos: Vector3<Float>,
+    frustum: Option<Frustum>,
+    culled_count: usize
+}
+
+// meshes of one material with their instances
+type MaterialGroup<'a> = (&'a Material, Vec<(&'a Mesh, &'a [Matrix4<Float>])>);
+
+// the prototype data is taken on every clear, so reloaded prototypes are used from the next frame on
+struct Batch {
+    prototype: Arc<ObjectPrototype>,
+    data: Arc<PrototypeData>,
+    transforms: Vec<Matrix4<Float>>
+}
+
+impl InstanceBatch {
+    pub fn add(&mut self, object: &Object) {
+        let prototype = object.get_prototype();
+        // taken once, as it needs a lock of the prototype
+        let data = prototype.get_data();
+        if let Some(ref frustum) = self.frustum {
+            let sphere = data.get_bounding_sphere().transform(object.get_model_matrix());
+            if !frustum.intersects_sphere(&sphere) {
+                self.culled_count += 1;
+                return;
+            }
+        }
+        // every prototype has its own lod distances
+        let lod = data.select_lod(length(object.get_world_translation() - self.view_pos));
+        // prototypes are shared, so their address identifies them
+        let key = (Arc::as_ptr(prototype) as usize, lod);
+        self.batches.entry(key)
+            .or_insert_with(|| Batch {
+                prototype: prototype.clone(),
+                data: data,
+                transforms: Vec::new()
+            })
+            .transforms.push(*object.get_model_matrix());
+    }
+
+    // drops batches, which had no instances since the last clear,
+    // the following objects get culled and their lods chosen by the camera
+    pub fn clear(&mut self, camera: &Camera) {
+        self.batches.retain(|_, batch| !batch.transforms.is_empty());
+        self.batches.values_mut().for_each(|batch| {
+            batch.transforms.clear();
+            batch.data = batch.prototype.get_data();
+        });
+        self.view_pos = camera.get_world_translation();
+        self.frustum = Some(Frustum::from_matrix(&camera.create_view_projection_matrix()));
+        self.culled_count = 0;
+    }
+
+    // the mesh parts of all batches are grouped by their material, so every material gets bound once
+    pub fn render_meshes(&self, info: &mut RenderInfo) -> Result<(), GraphicsError> {
+        let view_projection = info.get_camera().create_view_projection_matrix();
+        for (material, meshes) in self.group_by_material().values() {
+            material.bind();
+            let result = material.get_shader().set_resource_mat4("view_projection", &view_projection)
+                .map_err(GraphicsError::from)
+                .and_then(|_| meshes.iter().try_for_each(|(mesh, transforms)| mesh.render_instanced(transforms)));
+            material.unbind();
+            result?;
+        }
+        Ok(())
+    }
+
+    // impostors need their own shader, so they are drawn separately
+    pub fn render_impostors(&self, info: &mut RenderInfo) -> Result<(), GraphicsError> {
+        for ((_, lod), batch) in self.batches.iter() {
+            if batch.data.is_impostor_lod(*lod) && !batch.transforms.is_empty() {
+                batch.data.render_impostor_instanced(info, &batch.transforms)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_draw_call_count(&self) -> usize {
+        self.batches.iter()
+            .filter(|(_, batch)| !batch.transforms.is_empty())
+            .map(|((_, lod), batch)| if batch.data.is_impostor_lod(*lod) {
+                1
+            } else {
+                batch.data.get_parts(*lod).len()
+            })
+            .sum()
+    }
+
+    pub fn get_material_count(&self) -> usize {
+        self.group_by_material().len()
+    }
+
+    pub fn get_instance_count(&self) -> usize {
+        self.batches.values().map(|batch| batch.transforms.len()).sum()
+    }
+
+    pub fn get_culled_count(&self) -> usize {
+        self.culled_count
+    }
+
+    // instances drawn with each mesh lod, impostors are counted separately
+    pub fn get_instance_counts_by_lod(&self) -> (Vec<usize>, usize) {
+        let mut counts = Vec::new();
+        let mut impostor_count = 0;
+        for ((_, lod), batch) in self.batches.iter() {
+            if batch.data.is_impostor_lod(*lod) {
+                impostor_count += batch.transforms.len();
+                continue;
+            }
+            let lod = *lod as usize;
+            if counts.len() <= lod {
+                counts.resize(lod + 1, 0);
+            }
+            counts[lod] += batch.transforms.len();
+        }
+        (counts, impostor_count)
+    }
+
+    // meshes with their instances by the address of their material
+    fn group_by_material(&self) -> BTreeMap<usize, MaterialGroup<'_>> {
+        let mut groups: BTreeMap<usize, MaterialGroup> = BTreeMap::new();
+        for ((_, lod), batch) in self.batches.iter() {
+            if batch.data.is_impostor_lod(*lod) || batch.transforms.is_empty() {
+                continue;
+            }
+            for part in batch.data.get_parts(*lod) {
+                let material = part.get_material();
+                groups.entry(Arc::as_ptr(material) as usize)
+                    .or_insert_with(|| (material.as_ref(), Vec::new()))
+                    .1.push((part.get_mesh(), &batch.transforms));
+            }
+        }
+        groups
+    }
+}
+
+impl Default for InstanceBatch {
+    fn default() -> Self {
+        Self {
+            batches: BTreeMap::new(),
+            view_pos: Vector3::new(0., 0., 0.),
+            frustum: None,
+            culled_count: 0
+        }
+    }
+}
@@ -14,14 +14,31 @@ extern crate core;
 
 pub mod world;
 pub mod world_error;
+pub mod ray_hit;
+pub mod chunk_event;
+pub mod chunk_heights;
+pub mod coordinates;
+pub mod object_id;
 mod chunk;
+mod height_sampler;
+mod instance_batch;
+mod object_edits;
+mod raycast;
 mod noise;
 mod surface;
 
 pub use self::world::World;
 pub use self::world_error::WorldError;
+pub use self::ray_hit::RayHit;
+pub use self::chunk_event::ChunkEvent;
+pub use self::chunk_heights::ChunkHeights;
+pub use self::coordinates::{ WorldPos, ChunkPos, LocalPos };
+pub use self::object_id::ObjectId;
 
 use self::surface::{ Terrain, TerrainSet, TerrainType };
+use self::height_sampler::HeightSampler;
+use self::instance_batch::InstanceBatch;
+use self::object_edits::ObjectEdits;
 
 
 
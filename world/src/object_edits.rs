@@ -0,0 +1,57 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use core::Object;
+use crate::chunk::Chunk;
+use crate::{ ChunkPos, ObjectId };
+
+// objects removed from or inserted into chunks at runtime, which get applied again,
+// when a chunk is rebuilt, so the changes survive lod changes and reloads
+#[derive(Default)]
+pub struct ObjectEdits {
+    chunks: BTreeMap<ChunkPos, ChunkEdits>
+}
+
+#[derive(Default)]
+struct ChunkEdits {
+    // slots of spawned objects
+    removed: BTreeSet<usize>,
+    // objects positioned relative to their chunk
+    inserted: BTreeMap<usize, Object>,
+    // inserted slots are never reused, so ids of removed objects don't point to new ones
+    next_inserted_slot: usize
+}
+
+impl ObjectEdits {
+    // returns the id for the object, from the slots apart of the spawned objects
+    pub fn insert(&mut self, chunk_pos: ChunkPos, local_object: Object) -> ObjectId {
+        let edits = self.chunks.entry(chunk_pos).or_default();
+        let slot = ObjectId::FIRST_INSERTED_SLOT + edits.next_inserted_slot;
+        edits.next_inserted_slot += 1;
+        edits.inserted.insert(slot, local_object);
+        ObjectId::new(chunk_pos, slot)
+    }
+
+    pub fn remove(&mut self, id: ObjectId) {
+        let edits = self.chunks.entry(id.get_chunk_pos()).or_default();
+        if edits.inserted.remove(&id.get_slot()).is_none() {
+            edits.removed.insert(id.get_slot());
+        }
+    }
+
+    pub fn apply(&self, pos: ChunkPos, chunk: &mut Chunk) {
+        if let Some(edits) = self.chunks.get(&pos) {
+            for slot in edits.removed.iter() {
+                chunk.remove_object(*slot);
+            }
+            for (slot, object) in edits.inserted.iter() {
+                chunk.insert_object(*slot, object.clone());
+            }
+        }
+    }
+
+    pub fn get_edit_count(&self) -> usize {
+        self.chunks.values()
+            .map(|edits| edits.removed.len() + edits.inserted.len())
+            .sum()
+    }
+}
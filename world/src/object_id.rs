@@ -0,0 +1,38 @@
+use std::fmt;
+
+use crate::ChunkPos;
+
+// identifies a placed object by its chunk and its slot within the chunk, slots are never reused,
+// so the id stays valid until the object is removed, also over rebuilds of the chunk
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectId {
+    chunk_pos: ChunkPos,
+    slot: usize
+}
+
+impl ObjectId {
+    // objects inserted at runtime get their own slots from here on, so they can't collide
+    // with the spawned objects, whose count depends on the lod the chunk was built with
+    pub const FIRST_INSERTED_SLOT: usize = 1 << 24;
+
+    pub fn new(chunk_pos: ChunkPos, slot: usize) -> Self {
+        Self {
+            chunk_pos: chunk_pos,
+            slot: slot
+        }
+    }
+
+    pub fn get_chunk_pos(&self) -> ChunkPos {
+        self.chunk_pos
+    }
+
+    pub fn get_slot(&self) -> usize {
+        self.slot
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}#{}", self.chunk_pos, self.slot)
+    }
+}
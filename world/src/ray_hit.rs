@@ -0,0 +1,54 @@
+use glm::Vector3;
+
+use core::{ Float, Object };
+use crate::{ ChunkPos, ObjectId };
+
+pub struct RayHit<'a> {
+    position: Vector3<Float>,
+    normal: Vector3<Float>,
+    distance: Float,
+    chunk_pos: ChunkPos,
+    object: Option<(ObjectId, &'a Object)>
+}
+
+impl<'a> RayHit<'a> {
+    pub fn new(
+        position: Vector3<Float>,
+        normal: Vector3<Float>,
+        distance: Float,
+        chunk_pos: ChunkPos,
+        object: Option<(ObjectId, &'a Object)>) -> Self {
+        Self {
+            position: position,
+            normal: normal,
+            distance: distance,
+            chunk_pos: chunk_pos,
+            object: object
+        }
+    }
+
+    pub fn get_position(&self) -> Vector3<Float> {
+        self.position
+    }
+
+    pub fn get_normal(&self) -> Vector3<Float> {
+        self.normal
+    }
+
+    pub fn get_distance(&self) -> Float {
+        self.distance
+    }
+
+    pub fn get_chunk_pos(&self) -> ChunkPos {
+        self.chunk_pos
+    }
+
+    // none, if the terrain was hit
+    pub fn get_object(&self) -> Option<&'a Object> {
+        self.object.map(|(_, object)| object)
+    }
+
+    pub fn get_object_id(&self) -> Option<ObjectId> {
+        self.object.map(|(id, _)| id)
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::{ BTreeMap, BTreeSet };
+
+use glm::{ Vector3, normalize, length };
+
+use core::{ Float, Object };
+use crate::chunk::{ Chunk, CHUNK_SIZE };
+use crate::{ RayHit, ChunkPos, ObjectId, WorldPos };
+
+// walks the chunk grid along the ray, testing the terrain of each passed chunk and the objects
+// of the chunk and its neighbours, since objects can overlap chunk borders.
+// The ray is relative to the floating origin chunk.
+pub fn raycast<'a>(
+    chunks: &'a BTreeMap<ChunkPos, Chunk>,
+    chunk_origin: ChunkPos,
+    origin: Vector3<Float>,
+    direction: Vector3<Float>,
+    max_distance: Float) -> Option<RayHit<'a>> {
+    // a zero or invalid direction would turn every distance into nan
+    let direction_length = length(direction);
+    if !direction_length.is_finite() || direction_length <= 0. || !max_distance.is_finite() {
+        return None;
+    }
+    let direction = normalize(direction);
+    let chunk_size = CHUNK_SIZE as Float;
+    // nothing can be hit after the ray left the loaded chunks
+    let max_distance = Float::min(max_distance, get_exit_distance(chunks, chunk_origin, origin, direction)?);
+    let mut chunk_pos = WorldPos::from_relative(origin, chunk_origin).get_chunk_pos()?;
+    let start_cell = (chunk_pos - chunk_origin).to_array();
+    let step = [direction.x.signum() as i32, direction.y.signum() as i32];
+    let mut next_border = [0.; 2];
+    let mut border_delta = [0.; 2];
+    for i in 0..2 {
+        if direction[i] == 0. {
+            next_border[i] = Float::INFINITY;
+            border_delta[i] = Float::INFINITY;
+        } else {
+            let border_cell = if direction[i] > 0. { start_cell[i] + 1 } else { start_cell[i] };
+            let border = border_cell as Float * chunk_size;
+            next_border[i] = (border - origin[i]) / direction[i];
+            border_delta[i] = chunk_size / direction[i].abs();
+        }
+    }
+
+    let mut tested_objects: BTreeSet<ChunkPos> = BTreeSet::new();
+    let mut nearest: Option<RayHit<'a>> = None;
+    let mut cell_entry: Float = 0.;
+    while cell_entry <= max_distance {
+        let limit = match nearest {
+            Some(ref hit) if hit.get_distance() <= cell_entry => break,
+            Some(ref hit) => hit.get_distance(),
+            None => max_distance
+        };
+        if let Some(hit) = raycast_chunk_objects(chunks, chunk_pos, &mut tested_objects, origin, direction, limit) {
+            nearest = Some(hit);
+        }
+        let limit = nearest.as_ref().map_or(max_distance, |hit| hit.get_distance());
+        if let Some(chunk) = chunks.get(&chunk_pos) {
+            if let Some((distance, normal)) = chunk.raycast_terrain(origin, direction, limit) {
+                nearest = Some(RayHit::new(origin + direction * distance, normal, distance, chunk_pos, None));
+            }
+        }
+
+        let axis = if next_border[0] < next_border[1] { 0 } else { 1 };
+        cell_entry = next_border[axis];
+        chunk_pos = if axis == 0 { chunk_pos.offset(step[0], 0) } else { chunk_pos.offset(0, step[1]) };
+        next_border[axis] += border_delta[axis];
+    }
+    nearest
+}
+
+// distance, at which the ray leaves the rectangle around the loaded chunks and their objects,
+// none if the ray doesn't reach it
+fn get_exit_distance(
+    chunks: &BTreeMap<ChunkPos, Chunk>,
+    chunk_origin: ChunkPos,
+    origin: Vector3<Float>,
+    direction: Vector3<Float>) -> Option<Float> {
+    let mut min = chunks.keys().next()?.to_array();
+    let mut max = min;
+    for pos in chunks.keys() {
+        for (i, value) in pos.to_array().iter().enumerate() {
+            min[i] = i32::min(min[i], *value);
+            max[i] = i32::max(max[i], *value);
+        }
+    }
+    let origin_cell = chunk_origin.to_array();
+    let chunk_size = CHUNK_SIZE as Float;
+    let mut exit_distance = Float::INFINITY;
+    for i in 0..2 {
+        // objects reach up to one chunk over the border
+        let border = match direction[i] {
+            d if d > 0. => (max[i] - origin_cell[i] + 2) as Float * chunk_size,
+            d if d < 0. => (min[i] - origin_cell[i] - 1) as Float * chunk_size,
+            _ => continue
+        };
+        exit_distance = Float::min(exit_distance, (border - origin[i]) / direction[i]);
+    }
+    if exit_distance < 0. {
+        return None;
+    }
+    Some(exit_distance)
+}
+
+fn raycast_chunk_objects<'a>(
+    chunks: &'a BTreeMap<ChunkPos, Chunk>,
+    center: ChunkPos,
+    tested_objects: &mut BTreeSet<ChunkPos>,
+    origin: Vector3<Float>,
+    direction: Vector3<Float>,
+    max_distance: Float) -> Option<RayHit<'a>> {
+    let mut nearest: Option<RayHit<'a>> = None;
+    for y in -1..2 {
+        for x in -1..2 {
+            let chunk_pos = center.offset(x, y);
+            if !tested_objects.insert(chunk_pos) {
+                continue;
+            }
+            let chunk = match chunks.get(&chunk_pos) {
+                Some(chunk) => chunk,
+                None => continue
+            };
+            for (slot, object) in chunk.get_objects() {
+                let limit = nearest.as_ref().map_or(max_distance, |hit| hit.get_distance());
+                let id = ObjectId::new(chunk_pos, slot);
+                if let Some(hit) = raycast_object(id, object, chunk_pos, origin, direction, limit) {
+                    nearest = Some(hit);
+                }
+            }
+        }
+    }
+    nearest
+}
+
+fn raycast_object<'a>(
+    id: ObjectId,
+    object: &'a Object,
+    chunk_pos: ChunkPos,
+    origin: Vector3<Float>,
+    direction: Vector3<Float>,
+    max_distance: Float) -> Option<RayHit<'a>> {
+    if !object.get_bounding_sphere().intersects_ray(origin, direction, max_distance) {
+        return None;
+    }
+    let bounds = object.get_bounds();
+    match bounds.intersect_ray(origin, direction) {
+        Some(distance) if distance <= max_distance => {
+            let position = origin + direction * distance;
+            let normal = bounds.get_face_normal(position);
+            Some(RayHit::new(position, normal, distance, chunk_pos, Some((id, object))))
+        },
+        _ => None
+    }
+}
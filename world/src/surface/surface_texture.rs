@@ -9,7 +9,11 @@ use super::{ TerrainSet, Terrain, TerrainType };
 
 pub struct SurfaceTexture {
     texture: Texture,
-    terrain_set: TerrainSet
+    terrain_set: TerrainSet,
+    // kept for reloading the image
+    img_path: String,
+    sub_size: [u32; 2],
+    coordinates: Vec<[u32; 3]>
 }
 
 impl SurfaceTexture {
@@ -18,22 +22,36 @@ impl SurfaceTexture {
         let file_info: FileInfo = serde_yaml::from_str(&content)?;
         let mut terrain_set = TerrainSet::default();
 
-        let mut builder = TextureBuilder::new_2d_array(file_info.get_path(), file_info.get_size_as_array());
+        let mut coordinates = Vec::new();
         for (terrain_type, coord) in file_info.get_coordinates().iter() {
-            builder.add_array_element(*coord);
+            coordinates.push(*coord);
             if let Some(_existing) = terrain_set.insert(*terrain_type, Terrain::new(*terrain_type, coord[2])) {
                 warn!("Terrain of type '{}' already existing!", terrain_type);
             }
         }
-        let texture_array = builder.finish()?;
+        let texture_array = create_texture_array(file_info.get_path(), file_info.get_size_as_array(), &coordinates)?;
 
         let surface_texture = SurfaceTexture {
             texture: texture_array,
-            terrain_set: terrain_set
+            terrain_set: terrain_set,
+            img_path: file_info.get_path().to_string(),
+            sub_size: file_info.get_size_as_array(),
+            coordinates: coordinates
         };
         Ok(surface_texture)
     }
 
+    pub fn get_img_path(&self) -> &str {
+        &self.img_path
+    }
+
+    // uploads the image again with the same terrain coordinates, the previous texture is kept on errors
+    pub fn reload(&mut self) -> Result<(), WorldError> {
+        info!("Reloading surface texture '{}'", self.img_path);
+        self.texture = create_texture_array(&self.img_path, self.sub_size, &self.coordinates)?;
+        Ok(())
+    }
+
     pub fn get_terrain_set(&self) -> &TerrainSet {
         &self.terrain_set
     }
@@ -47,6 +65,14 @@ impl SurfaceTexture {
     }
 }
 
+fn create_texture_array(img_path: &str, sub_size: [u32; 2], coordinates: &[[u32; 3]]) -> Result<Texture, WorldError> {
+    let mut builder = TextureBuilder::new_2d_array(img_path, sub_size);
+    for coord in coordinates.iter() {
+        builder.add_array_element(*coord);
+    }
+    Ok(builder.finish()?)
+}
+
 #[derive(Deserialize)]
 struct FileInfo {
     texture_size: u32,
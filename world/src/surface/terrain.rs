@@ -20,7 +20,6 @@ impl Terrain {
         }
     }
 
-    #[allow(unused)]
     pub fn get_type(&self) -> TerrainType {
         self.terrain_type
     }
@@ -9,6 +9,17 @@ pub enum TerrainType {
     Rock
 }
 
+impl TerrainType {
+    pub fn from_name(name: &str) -> Option<TerrainType> {
+        match name.to_lowercase().as_str() {
+            "grass" => Some(TerrainType::Grass),
+            "mud" => Some(TerrainType::Mud),
+            "rock" => Some(TerrainType::Rock),
+            _ => None
+        }
+    }
+}
+
 impl fmt::Display for TerrainType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -1,39 +1,65 @@
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
-use glm::{normalize, GenNum, Vector3};
+use glm::{length, normalize, GenNum, Vector2, Vector3};
 use rand;
 use rand::rngs::StdRng;
 #[allow(unused)]
 use rand::{FromEntropy, Rng, SeedableRng};
 
-use crate::chunk::{chunk_size::get_chunk_pos, Chunk, ChunkLoader, CHUNK_SIZE};
+use crate::chunk::{
+    CachedChunk, Chunk, ChunkCache, ChunkLoader, ChunkRequest, HeightFormat, MeshSettings,
+    RetryQueue, UploadBudget, CHUNK_SIZE,
+};
+use crate::raycast::raycast;
 use crate::surface::SurfaceTexture;
-use crate::WorldError;
+use crate::{
+    ChunkEvent, ChunkPos, HeightSampler, InstanceBatch, ObjectEdits, ObjectId, RayHit, WorldError,
+    WorldPos,
+};
+use core::file::FileWatcher;
+use core::format::format_number;
 use core::graphics::{GraphicsError, ShaderProgram, ShaderProgramBuilder};
+use core::object::ImpostorBaker;
 use core::traits::{RenderInfo, Renderable, Rotatable, Scalable, Translatable, Updatable};
-use core::{distance::get_distance_2d_from_zero, format::format_number};
 use core::{Config, Float, Object, ObjectManager, Player, Skybox, Sun, Timer, UpdateError};
 
 pub struct World {
     surface_texture: SurfaceTexture,
     surface_shader_program: Rc<ShaderProgram>,
+    impostor_shader_program: Rc<ShaderProgram>,
+    instance_batch: RefCell<InstanceBatch>,
     skybox: Skybox,
     sun: Sun,
     chunk_loader: ChunkLoader,
-    chunks: BTreeMap<[i32; 2], Chunk>,
-    chunk_update_timer: Timer,
+    chunks: BTreeMap<ChunkPos, Chunk>,
+    object_edits: ObjectEdits,
+    chunk_cache: ChunkCache,
+    chunk_observers: Vec<Box<dyn FnMut(&ChunkEvent)>>,
+    height_sampler: HeightSampler,
     chunk_build_stats_timer: Timer,
+    // polls the asset files, none if hot reloading is disabled
+    asset_reload_timer: Option<Timer>,
+    asset_watcher: FileWatcher,
+    impostor_baker: Option<ImpostorBaker>,
     lod_near_radius: i32,
     lod_far_radius: i32,
     active_chunk_radius: i32,
-    last_chunk_load: [i32; 2],
-    #[allow(unused)]
+    last_chunk_load: ChunkPos,
     object_manager: Arc<ObjectManager>,
     test_monkey: Object,
     center: Vector3<Float>,
+    origin: ChunkPos,
+    rebase_distance: Float,
+    view_direction: Vector3<Float>,
     gravity: Float,
+    player_radius: Float,
+    player_height: Float,
+    error_count: u32,
 }
 
 impl World {
@@ -43,23 +69,48 @@ impl World {
         let skybox_img_path = config.get_str("skybox_img_path")?;
         let surface_texture_info_path = config.get_str("surface_info_path")?;
         let gravity = config.get_float_or_default("gravity", 0.25);
+        let rebase_distance = config.get_float_or_default("floating_origin_distance", 4096.);
+        let player_radius = config.get_float_or_default("player_radius", 0.4);
+        let player_height = config.get_float_or_default("player_height", 2.);
 
         let surface_shader_program = load_surface_shader(config)?;
+        let impostor_shader_program = load_impostor_shader(config)?;
         let surface_texture = SurfaceTexture::load(surface_texture_info_path)?;
 
         let (near_radius, far_radius, active_radius) = get_chunk_radii(config);
+        let chunk_cache = create_chunk_cache(config);
+        let (thread_count, output_capacity) = get_chunk_loader_settings(config);
+        let height_sample_cache_size = config.get_uint_or_default("height_sample_cache_size", 4096);
 
         info!("Day length is {}s", day_length);
         info!("Gravity is {}", gravity);
+        info!("Floating origin moves at distance {}", rebase_distance);
+        info!(
+            "Player collides as cylinder with radius {}, height {}",
+            player_radius, player_height
+        );
 
         //let mut rng = StdRng::seed_from_u64(0);
         let mut rng = StdRng::from_entropy();
 
-        let object_manager = Arc::new(ObjectManager::from_yaml(&object_prototypes_path)?);
+        let impostor_baker = create_impostor_baker(config)?;
+        let object_manager = Arc::new(ObjectManager::from_yaml(
+            &object_prototypes_path,
+            get_object_lod_distances(config),
+            impostor_baker.as_ref(),
+        )?);
         let chunk_loader = ChunkLoader::new(
             &mut rng,
             object_manager.clone(),
             surface_texture.get_terrain_set(),
+            output_capacity,
+            create_upload_budget(config),
+            create_retry_queue(config),
+            create_mesh_settings(config),
+        );
+        let height_sampler = HeightSampler::new(
+            chunk_loader.get_architect(),
+            height_sample_cache_size as usize,
         );
 
         let mut test_monkey = object_manager.create_object("monkey")?;
@@ -69,25 +120,40 @@ impl World {
         let mut world = World {
             surface_texture: surface_texture,
             surface_shader_program: Rc::new(surface_shader_program),
+            impostor_shader_program: Rc::new(impostor_shader_program),
+            instance_batch: RefCell::new(InstanceBatch::default()),
             skybox: Skybox::new(skybox_img_path)?,
             sun: Sun::with_day_length(day_length),
             chunk_loader: chunk_loader,
             chunks: BTreeMap::new(),
-            chunk_update_timer: Timer::new(500),
+            object_edits: ObjectEdits::default(),
+            chunk_cache: chunk_cache,
+            chunk_observers: Vec::new(),
+            height_sampler: height_sampler,
             chunk_build_stats_timer: Timer::new(5000),
+            asset_reload_timer: create_asset_reload_timer(config),
+            asset_watcher: FileWatcher::default(),
+            impostor_baker: impostor_baker,
             lod_near_radius: near_radius,
             lod_far_radius: far_radius,
             active_chunk_radius: active_radius,
-            last_chunk_load: [0, 0],
+            last_chunk_load: ChunkPos::default(),
             object_manager: object_manager,
             test_monkey: test_monkey,
             center: Vector3::from_s(0.),
+            origin: ChunkPos::default(),
+            rebase_distance: rebase_distance,
+            view_direction: Vector3::new(1., 0., 0.),
             gravity: gravity,
+            player_radius: player_radius,
+            player_height: player_height,
+            error_count: 0,
         };
 
         world.update_skybox_size();
+        world.watch_asset_files();
 
-        world.chunk_loader.start(8);
+        world.chunk_loader.start(thread_count);
         world.request_chunks()?;
 
         Ok(world)
@@ -97,15 +163,18 @@ impl World {
         (self.active_chunk_radius * CHUNK_SIZE * 8) as Float
     }
 
+    // also moves the floating origin with the player, rendered positions are always relative to it
     pub fn interact(&mut self, player: &mut Player) -> Result<(), WorldError> {
+        self.update_origin(player);
         let player_pos = player.get_translation();
 
         let chunk_height = match self.get_chunk_by_world_pos(player_pos) {
-            Some(chunk) => {
-                let player_pos_xy = player_pos.truncate(2);
-                let height = chunk.get_height(player_pos_xy);
+            Some(_) => {
+                let world_pos = self.to_absolute(player_pos);
+                let height = self.sample_height(world_pos);
                 let forward_xy = normalize(player.get_direction().truncate(2));
-                let forward_height = chunk.get_height(player_pos_xy + forward_xy);
+                let forward_height =
+                    self.sample_height(world_pos.offset(forward_xy.x as f64, forward_xy.y as f64));
                 let forward_z = forward_height - height;
 
                 player.update_forward(forward_xy.extend(forward_z as Float));
@@ -133,38 +202,203 @@ impl World {
             player.set_z(chunk_height as Float);
         }
 
+        self.collide_with_objects(player);
         Ok(())
     }
 
+    // absolute world position of the floating origin
+    pub fn get_origin(&self) -> WorldPos {
+        self.origin.get_world_pos()
+    }
+
+    pub fn to_absolute(&self, pos: Vector3<Float>) -> WorldPos {
+        WorldPos::from_relative(pos, self.origin)
+    }
+
+    // exact terrain height from the generator in absolute world coordinates,
+    // independent of the loaded chunk lods
+    pub fn sample_height(&self, world_pos: WorldPos) -> f64 {
+        self.height_sampler.sample_height(world_pos)
+    }
+
+    pub fn sample_normal(&self, world_pos: WorldPos) -> Vector3<Float> {
+        self.height_sampler.sample_normal(world_pos)
+    }
+
+    // nearest hit on loaded terrain or placed objects, positions are relative to the floating origin
+    pub fn raycast(
+        &self,
+        origin: Vector3<Float>,
+        direction: Vector3<Float>,
+        max_distance: Float,
+    ) -> Option<RayHit<'_>> {
+        raycast(&self.chunks, self.origin, origin, direction, max_distance)
+    }
+
+    // placed objects standing within the rectangle, positions are relative to the floating origin
+    pub fn objects_in_rect(
+        &self,
+        min: Vector2<Float>,
+        max: Vector2<Float>,
+    ) -> Vec<(ObjectId, &Object)> {
+        let mut objects = Vec::new();
+        for (chunk_pos, chunk) in self.get_chunks_in_rect(min, max) {
+            objects.extend(
+                chunk
+                    .get_objects_in_rect(min, max)
+                    .filter(|(_, o)| {
+                        let pos = o.get_world_translation();
+                        pos.x >= min.x && pos.y >= min.y && pos.x <= max.x && pos.y <= max.y
+                    })
+                    .map(|(slot, o)| (ObjectId::new(chunk_pos, slot), o)),
+            );
+        }
+        objects
+    }
+
+    pub fn objects_in_radius(
+        &self,
+        center: Vector2<Float>,
+        radius: Float,
+    ) -> Vec<(ObjectId, &Object)> {
+        let mut objects = self.objects_in_rect(center - radius, center + radius);
+        objects.retain(|(_, o)| length(o.get_world_translation().truncate(2) - center) <= radius);
+        objects
+    }
+
+    // nearest object of the prototype within the distance, the search radius grows,
+    // until an object is found
+    pub fn nearest_object(
+        &self,
+        pos: Vector2<Float>,
+        kind: &str,
+        max_distance: Float,
+    ) -> Option<(ObjectId, &Object)> {
+        let get_distance = |o: &Object| length(o.get_world_translation().truncate(2) - pos);
+        // objects stand within their chunks, so none is farther away than the farthest loaded corner
+        let (min, max) = self.get_loaded_rect()?;
+        let corners = [
+            min,
+            max,
+            Vector2::new(min.x, max.y),
+            Vector2::new(max.x, min.y),
+        ];
+        let loaded_distance = corners
+            .iter()
+            .map(|corner| length(*corner - pos))
+            .fold(0., Float::max);
+        let max_distance = Float::min(max_distance, loaded_distance);
+        let mut radius = Float::min(CHUNK_SIZE as Float / 4., max_distance);
+        loop {
+            let nearest = self
+                .objects_in_radius(pos, radius)
+                .into_iter()
+                .filter(|(_, o)| o.get_prototype().get_name() == kind)
+                .min_by(|(_, a), (_, b)| get_distance(a).total_cmp(&get_distance(b)));
+            if nearest.is_some() || radius >= max_distance {
+                return nearest;
+            }
+            radius = Float::min(2. * radius, max_distance);
+        }
+    }
+
+    // rectangle covering the loaded chunks relative to the floating origin, none if nothing is loaded
+    fn get_loaded_rect(&self) -> Option<(Vector2<Float>, Vector2<Float>)> {
+        let first = self.chunks.keys().next()?.to_array();
+        let (min, max) = self.chunks.keys().fold((first, first), |(min, max), pos| {
+            let pos = pos.to_array();
+            (
+                [i32::min(min[0], pos[0]), i32::min(min[1], pos[1])],
+                [i32::max(max[0], pos[0]), i32::max(max[1], pos[1])],
+            )
+        });
+        let origin = self.origin.to_array();
+        let to_relative = |cell: [i32; 2]| {
+            Vector2::new(
+                ((cell[0] - origin[0]) * CHUNK_SIZE) as Float,
+                ((cell[1] - origin[1]) * CHUNK_SIZE) as Float,
+            )
+        };
+        Some((to_relative(min), to_relative([max[0] + 1, max[1] + 1])))
+    }
+
+    pub fn get_object(&self, id: ObjectId) -> Option<&Object> {
+        self.chunks
+            .get(&id.get_chunk_pos())
+            .and_then(|chunk| chunk.get_object(id.get_slot()))
+    }
+
+    // places the object into the loaded chunk at its position, which is relative to the floating origin,
+    // returns none if there is no chunk loaded
+    pub fn insert_object(&mut self, object: Object) -> Option<ObjectId> {
+        let chunk_pos = self.to_absolute(object.get_translation()).get_chunk_pos()?;
+        let chunk = self.chunks.get_mut(&chunk_pos)?;
+        let mut local_object = object;
+        local_object.mod_translation(-chunk.get_offset());
+        let id = self.object_edits.insert(chunk_pos, local_object.clone());
+        chunk.insert_object(id.get_slot(), local_object);
+        Some(id)
+    }
+
+    pub fn remove_object(&mut self, id: ObjectId) -> Option<Object> {
+        let object = self
+            .chunks
+            .get_mut(&id.get_chunk_pos())?
+            .remove_object(id.get_slot())?;
+        self.object_edits.remove(id);
+        Some(object)
+    }
+
     pub fn request_chunks(&mut self) -> Result<(), WorldError> {
-        let mut request_list: Vec<([i32; 2], u8)> = Vec::new();
-        let player_chunk_pos = get_chunk_pos(self.center);
+        let mut request_list: Vec<ChunkRequest> = Vec::new();
+        let mut reuse_count = 0;
+        let player_chunk_pos = self.get_center_chunk_pos();
         for y in -self.active_chunk_radius..self.active_chunk_radius + 1 {
             for x in -self.active_chunk_radius..self.active_chunk_radius + 1 {
-                if let Some(pos_lod) = self.should_load_chunk([x, y], player_chunk_pos) {
-                    request_list.push(pos_lod);
+                if let Some((pos, lod)) =
+                    self.should_load_chunk(ChunkPos::new(x, y), player_chunk_pos)
+                {
+                    if self.chunks.contains_key(&pos) {
+                        request_list.push(ChunkRequest::new(pos, lod));
+                        continue;
+                    }
+                    match self.chunk_cache.take(pos, lod) {
+                        Some(CachedChunk::Complete(chunk)) => {
+                            self.insert_chunk(pos, chunk);
+                            reuse_count += 1;
+                        }
+                        Some(CachedChunk::Surface(height_map)) => {
+                            request_list.push(ChunkRequest::with_height_map(pos, lod, height_map));
+                        }
+                        None => request_list.push(ChunkRequest::new(pos, lod)),
+                    }
                 }
             }
         }
-        self.chunk_loader.request(&request_list)?;
+        trace!(
+            "Requested chunks: {}, reused from cache: {}",
+            request_list.len(),
+            reuse_count
+        );
+        self.chunk_loader.request(request_list)?;
         self.last_chunk_load = player_chunk_pos;
-        trace!("Requested chunks: {}", request_list.len());
         Ok(())
     }
 
     pub fn unload_distant_chunks(&mut self) {
         let mut unload_list = Vec::new();
-        let cam_pos = get_chunk_pos(self.center);
+        let cam_pos = self.get_center_chunk_pos();
         for chunk_pos in self.chunks.keys() {
-            let vec = [cam_pos[0] - chunk_pos[0], cam_pos[1] - chunk_pos[1]];
-            let distance = f32::sqrt((vec[0] * vec[0] + vec[1] * vec[1]) as f32).round() as i32;
+            let distance = cam_pos.get_distance(*chunk_pos).round() as i32;
             if distance >= self.active_chunk_radius {
                 unload_list.push(*chunk_pos);
             }
         }
         trace!("Unloading {} chunks", unload_list.len());
         for pos in unload_list {
-            self.chunks.remove(&pos);
+            if let Some(chunk) = self.remove_chunk(pos) {
+                self.chunk_cache.insert(chunk);
+            }
         }
     }
 
@@ -172,11 +406,61 @@ impl World {
         let finished_chunks = self.chunk_loader.get()?;
         if finished_chunks.len() > 0 {
             trace!("Finished chunks: {}", finished_chunks.len());
-            self.chunks.extend(finished_chunks);
+            for (pos, chunk) in finished_chunks {
+                self.chunk_cache.invalidate(pos);
+                self.insert_chunk(pos, chunk);
+            }
         }
         Ok(())
     }
 
+    // observers are called on chunk loads, lod changes and unloads
+    pub fn add_chunk_observer<F>(&mut self, observer: F)
+    where
+        F: FnMut(&ChunkEvent) + 'static,
+    {
+        self.chunk_observers.push(Box::new(observer));
+    }
+
+    fn insert_chunk(&mut self, pos: ChunkPos, mut chunk: Chunk) {
+        self.object_edits.apply(pos, &mut chunk);
+        chunk.set_origin(self.origin);
+        let previous_lod = self.chunks.get(&pos).map(|c| c.get_lod());
+        self.chunks.insert(pos, chunk);
+        if self.chunk_observers.is_empty() {
+            return;
+        }
+        let chunk = &self.chunks[&pos];
+        let event = match previous_lod {
+            // a rebuild at the same lod changes nothing for observers
+            Some(previous_lod) if previous_lod == chunk.get_lod() => return,
+            Some(previous_lod) => ChunkEvent::LodChanged {
+                pos: pos,
+                previous_lod: previous_lod,
+                lod: chunk.get_lod(),
+                heights: chunk.get_heights(),
+            },
+            None => ChunkEvent::Loaded {
+                pos: pos,
+                lod: chunk.get_lod(),
+                heights: chunk.get_heights(),
+            },
+        };
+        self.chunk_observers.iter_mut().for_each(|o| o(&event));
+    }
+
+    fn remove_chunk(&mut self, pos: ChunkPos) -> Option<Chunk> {
+        if let Some(chunk) = self.chunks.get(&pos) {
+            let event = ChunkEvent::Unloaded {
+                pos: pos,
+                lod: chunk.get_lod(),
+                heights: chunk.get_heights(),
+            };
+            self.chunk_observers.iter_mut().for_each(|o| o(&event));
+        }
+        self.chunks.remove(&pos)
+    }
+
     #[allow(dead_code)]
     pub fn count_loaded_vertices(&self) -> u32 {
         let mut vertex_count = 0;
@@ -190,11 +474,184 @@ impl World {
         self.center = pos;
     }
 
-    fn should_load_chunk(&self, pos: [i32; 2], player_pos: [i32; 2]) -> Option<([i32; 2], u8)> {
-        let distance = get_distance_2d_from_zero(pos).round() as i32;
+    pub fn set_view_direction(&mut self, direction: Vector3<Float>) {
+        self.view_direction = direction;
+    }
+
+    pub fn get_error_count(&self) -> u32 {
+        self.error_count + self.chunk_loader.get_failed_build_count()
+    }
+
+    fn get_avg_height_map_memory(&self) -> usize {
+        if self.chunks.is_empty() {
+            return 0;
+        }
+        let total: usize = self
+            .chunks
+            .values()
+            .map(|c| c.get_height_map_memory())
+            .sum();
+        total / self.chunks.len()
+    }
+
+    fn get_total_chunk_memory(&self) -> usize {
+        self.chunks.values().map(|c| c.get_memory_usage()).sum()
+    }
+
+    // keeps the last loading position for a center beyond the chunk range
+    fn get_center_chunk_pos(&self) -> ChunkPos {
+        self.to_absolute(self.center)
+            .get_chunk_pos()
+            .unwrap_or(self.last_chunk_load)
+    }
+
+    fn get_center_in_chunks(&self) -> Vector2<Float> {
+        Vector2::new(
+            self.origin.get_x() as Float + self.center.x / CHUNK_SIZE as Float,
+            self.origin.get_y() as Float + self.center.y / CHUNK_SIZE as Float,
+        )
+    }
+
+    // shifts the origin by whole chunks, once the player gets too far away from it
+    fn update_origin(&mut self, player: &mut Player) {
+        let player_pos = player.get_translation();
+        if Float::max(player_pos.x.abs(), player_pos.y.abs()) < self.rebase_distance {
+            return;
+        }
+        let new_origin = match self.to_absolute(player_pos).get_chunk_pos() {
+            Some(chunk_pos) => chunk_pos,
+            None => return,
+        };
+        let shift = new_origin - self.origin;
+        let offset = Vector3::new(
+            -(shift.get_x() * CHUNK_SIZE) as Float,
+            -(shift.get_y() * CHUNK_SIZE) as Float,
+            0.,
+        );
+        self.origin = new_origin;
+        player.mod_translation(offset);
+        self.center = self.center + offset;
+        self.test_monkey.mod_translation(offset);
+        let origin = self.origin;
+        self.chunks.values_mut().for_each(|c| c.set_origin(origin));
+        info!("Moved floating origin to chunk {}", self.origin);
+    }
+
+    // moves the player out of the colliders the next move would end up in, so it slides past them.
+    // only this move gets corrected, the momentum is kept, so pushes don't add up while jumping
+    fn collide_with_objects(&self, player: &mut Player) {
+        let momentum = player.get_momentum();
+        let mut target = player.get_translation() + momentum;
+        let mut offset = Vector2::new(0., 0.);
+        let chunk_pos = match self.to_absolute(target).get_chunk_pos() {
+            Some(chunk_pos) => chunk_pos,
+            None => return,
+        };
+        let min = target.truncate(2) - self.player_radius;
+        let max = target.truncate(2) + self.player_radius;
+        // objects can reach over chunk borders
+        for y in -1..2 {
+            for x in -1..2 {
+                let chunk = match self.chunks.get(&(chunk_pos + ChunkPos::new(x, y))) {
+                    Some(chunk) => chunk,
+                    None => continue,
+                };
+                for (_, object) in chunk.get_objects_in_rect(min, max) {
+                    let push = object.get_collider().and_then(|collider| {
+                        collider.get_push(target, self.player_radius, self.player_height)
+                    });
+                    if let Some(push) = push {
+                        target = target + push.extend(0.);
+                        offset = offset + push;
+                    }
+                }
+            }
+        }
+        if offset != Vector2::new(0., 0.) {
+            player.mod_translation(offset.extend(0.));
+        }
+    }
+
+    // the files the prototypes are created from can change with every reload
+    fn watch_asset_files(&mut self) {
+        if self.asset_reload_timer.is_none() {
+            return;
+        }
+        self.asset_watcher
+            .watch(self.surface_texture.get_img_path());
+        for path in self.object_manager.get_source_files() {
+            self.asset_watcher.watch(&path);
+        }
+        debug!(
+            "Watching {} asset files",
+            self.asset_watcher.get_file_count()
+        );
+    }
+
+    // changed assets replace the loaded ones in place, on errors the previous ones are kept
+    fn reload_changed_assets(&mut self) {
+        let changed_paths = self.asset_watcher.poll();
+        if changed_paths.is_empty() {
+            return;
+        }
+        info!("Changed asset files: {:?}", changed_paths);
+        if changed_paths
+            .iter()
+            .any(|path| path == self.surface_texture.get_img_path())
+        {
+            if let Err(e) = self.surface_texture.reload() {
+                self.handle_error(e);
+            }
+        }
+        let prototype_file = self.object_manager.get_file_path();
+        let errors = self
+            .object_manager
+            .reload(&changed_paths, self.impostor_baker.as_ref());
+        for e in errors {
+            self.handle_error(e.into());
+        }
+        if changed_paths.iter().any(|path| *path == prototype_file) {
+            if let Err(e) = self.respawn_objects() {
+                self.handle_error(e);
+            }
+        }
+        self.watch_asset_files();
+    }
+
+    // the spawn rules may have changed, so the loaded chunks get rebuilt at their lods
+    // and cached chunks with the previous objects are dropped
+    fn respawn_objects(&mut self) -> Result<(), WorldError> {
+        self.chunk_loader.update_spawners();
+        self.chunk_cache.clear();
+        let request_list = self
+            .chunks
+            .iter()
+            .map(|(pos, chunk)| ChunkRequest::new(*pos, chunk.get_lod()))
+            .collect();
+        self.chunk_loader.request(request_list)?;
+        Ok(())
+    }
+
+    fn handle_error(&mut self, error: WorldError) {
+        error!("{}", error);
+        self.error_count += 1;
+    }
+
+    fn cancel_distant_requests(&mut self) -> Result<(), WorldError> {
+        let cancel_count = self
+            .chunk_loader
+            .cancel_distant(self.get_center_chunk_pos(), self.active_chunk_radius)?;
+        if cancel_count > 0 {
+            trace!("Cancelled {} chunk requests", cancel_count);
+        }
+        Ok(())
+    }
+
+    fn should_load_chunk(&self, offset: ChunkPos, player_pos: ChunkPos) -> Option<(ChunkPos, u8)> {
+        let distance = offset.get_distance(ChunkPos::default()).round() as i32;
         if distance < self.active_chunk_radius {
             let lod = self.lod_by_chunk_distance(distance);
-            let chunk_pos = [player_pos[0] + pos[0], player_pos[1] + pos[1]];
+            let chunk_pos = player_pos + offset;
             match self.chunks.get(&chunk_pos) {
                 Some(c) => {
                     let old_lod = c.get_lod();
@@ -227,23 +684,54 @@ impl World {
     }
 
     fn update_shader_resources(&self) -> Result<(), GraphicsError> {
-        self.surface_shader_program.use_program();
-        self.surface_shader_program
-            .set_resource_vec3("view_pos", &self.center)?;
-        self.surface_shader_program
-            .set_resource_vec3("light_pos", &self.sun.calculate_position())?;
-
+        let light_pos = self.sun.calculate_position();
         let light_level = self.sun.calculate_light_level();
         let fog_color = Vector3::from_s(1. - (-light_level).exp());
-        self.surface_shader_program
-            .set_resource_vec3("fog_color", &fog_color)?;
+        let material_shaders = self.object_manager.get_materials();
+        for shader in [&self.impostor_shader_program, &self.surface_shader_program]
+            .iter()
+            .map(|shader| shader.as_ref())
+            .chain(
+                material_shaders
+                    .iter()
+                    .map(|material| material.get_shader()),
+            )
+        {
+            shader.use_program();
+            shader.set_resource_vec3("view_pos", &self.center)?;
+            shader.set_resource_vec3("light_pos", &light_pos)?;
+            shader.set_resource_vec3("fog_color", &fog_color)?;
+        }
         self.skybox.update_light_level(light_level)?;
         self.surface_shader_program.use_program();
         Ok(())
     }
 
-    fn get_chunk_by_world_pos(&self, world_pos: Vector3<Float>) -> Option<&Chunk> {
-        self.chunks.get(&get_chunk_pos(world_pos))
+    // loaded chunks covering the rectangle relative to the floating origin
+    fn get_chunks_in_rect(
+        &self,
+        min: Vector2<Float>,
+        max: Vector2<Float>,
+    ) -> Vec<(ChunkPos, &Chunk)> {
+        let first = self.to_absolute(min.extend(0.)).get_chunk_pos();
+        let last = self.to_absolute(max.extend(0.)).get_chunk_pos();
+        let (first, last) = match (first, last) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Vec::new(),
+        };
+        if first > last {
+            return Vec::new();
+        }
+        self.chunks
+            .range(first..=last)
+            .filter(|(pos, _)| pos.get_y() >= first.get_y() && pos.get_y() <= last.get_y())
+            .map(|(pos, chunk)| (*pos, chunk))
+            .collect()
+    }
+
+    fn get_chunk_by_world_pos(&self, pos: Vector3<Float>) -> Option<&Chunk> {
+        let chunk_pos = self.to_absolute(pos).get_chunk_pos()?;
+        self.chunks.get(&chunk_pos)
     }
 }
 
@@ -251,13 +739,26 @@ impl Renderable for World {
     fn render<'a>(&self, info: &'a mut RenderInfo) -> Result<(), GraphicsError> {
         self.surface_texture.activate();
         info.push_shader(self.surface_shader_program.clone());
-
-        self.test_monkey.render(info)?;
         self.chunks.values().try_for_each(|c| c.render(info))?;
+        info.pop_shader();
+        self.surface_texture.deactivate();
 
+        let mut instance_batch = self.instance_batch.borrow_mut();
+        instance_batch.clear(info.get_camera());
+        instance_batch.add(&self.test_monkey);
+        self.chunks
+            .values()
+            .for_each(|c| c.collect_objects(info, &mut instance_batch));
+        // every material binds its own shader and textures
+        instance_batch.render_meshes(info)?;
+
+        let view_projection = info.get_camera().create_view_projection_matrix();
+        info.push_shader(self.impostor_shader_program.clone());
+        self.impostor_shader_program
+            .set_resource_mat4("view_projection", &view_projection)?;
+        instance_batch.render_impostors(info)?;
         info.pop_shader();
 
-        self.surface_texture.deactivate();
         self.skybox.render(info)?;
         Ok(())
     }
@@ -265,28 +766,63 @@ impl Renderable for World {
 
 impl Updatable for World {
     fn tick(&mut self, time_passed: u32) -> Result<(), UpdateError> {
-        if self.chunk_update_timer.fires() {
-            if let Err(e) = self.get_finished_chunks() {
-                error!("{}", e); // TODO: handle error
+        if let Err(e) = self.get_finished_chunks() {
+            self.handle_error(e);
+        }
+        let cam_chunk_pos = self.get_center_chunk_pos();
+        if cam_chunk_pos.get_distance(self.last_chunk_load) > 2. {
+            self.unload_distant_chunks();
+            if let Err(e) = self.cancel_distant_requests() {
+                self.handle_error(e);
             }
-            let cam_chunk_pos = get_chunk_pos(self.center);
-            let vec = [
-                cam_chunk_pos[0] - self.last_chunk_load[0],
-                cam_chunk_pos[1] - self.last_chunk_load[1],
-            ];
-            if f32::sqrt((vec[0] * vec[0] + vec[1] * vec[1]) as f32) > 2. {
-                self.unload_distant_chunks();
-                if let Err(e) = self.request_chunks() {
-                    error!("{}", e); // TODO: handle error
-                }
+            if let Err(e) = self.request_chunks() {
+                self.handle_error(e);
             }
         }
+        if let Err(e) = self
+            .chunk_loader
+            .set_focus(self.get_center_in_chunks(), self.view_direction)
+        {
+            self.handle_error(e.into());
+        }
         if self.chunk_build_stats_timer.fires() {
             info!(
-                "Avg chunk build time = {:.2} ms, total chunk vertices = {}",
+                "Avg chunk build time = {:.2} ms, queued chunks = {}, chunks waiting for upload = {}, total chunk vertices = {}",
                 self.chunk_loader.get_avg_build_time(),
+                self.chunk_loader.get_queue_size(),
+                self.chunk_loader.get_upload_queue_size(),
                 format_number(self.count_loaded_vertices())
             );
+            info!(
+                "Chunk errors = {}, pending retries = {}, respawned loader threads = {}",
+                self.get_error_count(),
+                self.chunk_loader.get_retry_count(),
+                self.chunk_loader.get_respawn_count()
+            );
+            info!(
+                "Chunk memory: height maps = {:.1} kB per chunk, total = {:.1} MB",
+                self.get_avg_height_map_memory() as f64 / 1024.,
+                self.get_total_chunk_memory() as f64 / (1024. * 1024.)
+            );
+            let instance_batch = self.instance_batch.borrow();
+            let (lod_counts, impostor_count) = instance_batch.get_instance_counts_by_lod();
+            info!(
+                "Object draw calls = {}, materials = {}, object instances = {}, culled objects = {}, instances per lod = {:?}, impostors = {}",
+                instance_batch.get_draw_call_count(),
+                instance_batch.get_material_count(),
+                instance_batch.get_instance_count(),
+                instance_batch.get_culled_count(),
+                lod_counts,
+                impostor_count
+            );
+            info!("Object edits = {}", self.object_edits.get_edit_count());
+            info!(
+                "Chunk cache: entries = {}, memory = {:.1}/{:.1} MB, hit rate = {:.1}%",
+                self.chunk_cache.get_entry_count(),
+                self.chunk_cache.get_used_memory() as f64 / (1024. * 1024.),
+                self.chunk_cache.get_budget() as f64 / (1024. * 1024.),
+                100. * self.chunk_cache.get_hit_rate()
+            );
         }
 
         self.skybox.set_translation(self.center);
@@ -300,10 +836,20 @@ impl Updatable for World {
                 .mod_translation(Vector3::new(-500., 0., 0.));
         }
 
+        let reload_assets = match self.asset_reload_timer {
+            Some(ref mut timer) => {
+                timer.tick(time_passed)?;
+                timer.fires()
+            }
+            None => false,
+        };
+        if reload_assets {
+            self.reload_changed_assets();
+        }
+
         if let Err(e) = self.update_shader_resources() {
-            error!("{}", e); // TODO: handle error
+            self.handle_error(e.into());
         }
-        self.chunk_update_timer.tick(time_passed)?;
         self.chunk_build_stats_timer.tick(time_passed)?;
         Ok(())
     }
@@ -322,6 +868,112 @@ fn get_chunk_radii(config: &Config) -> (i32, i32, i32) {
     (near_radius, far_radius, active_radius)
 }
 
+// default distances for switching objects to lod 1 and to their impostor,
+// used for prototypes which don't give their own
+fn get_object_lod_distances(config: &Config) -> [Float; 2] {
+    let lod1_distance = config.get_float_or_default("object_lod1_distance", 80.);
+    let impostor_distance = Float::max(
+        config.get_float_or_default("object_impostor_distance", 250.),
+        lod1_distance,
+    );
+    info!(
+        "Objects switch to lod 1 at distance {}, to impostors at distance {}",
+        lod1_distance, impostor_distance
+    );
+    [lod1_distance, impostor_distance]
+}
+
+fn create_impostor_baker(config: &Config) -> Result<Option<ImpostorBaker>, WorldError> {
+    if !config.get_bool_or_default("object_impostors", true) {
+        info!("Object impostors are disabled");
+        return Ok(None);
+    }
+    let shader_dir = config.get_str("impostor_bake_shader_dir")?;
+    let resolution = u32::max(config.get_uint_or_default("impostor_resolution", 128), 1);
+    let view_count = u32::max(config.get_uint_or_default("impostor_view_count", 8), 1);
+    let baker = ImpostorBaker::new(shader_dir, resolution, view_count)?;
+    Ok(Some(baker))
+}
+
+// changed prototypes, obj files and the surface texture get reloaded, 0 disables it
+fn create_asset_reload_timer(config: &Config) -> Option<Timer> {
+    let interval = config.get_uint_or_default("asset_reload_interval", 1000);
+    if interval == 0 {
+        info!("Asset hot reloading is disabled");
+        return None;
+    }
+    info!("Asset files are checked for changes every {} ms", interval);
+    Some(Timer::new(interval))
+}
+
+fn get_chunk_loader_settings(config: &Config) -> (usize, usize) {
+    let default_thread_count = match thread::available_parallelism() {
+        Ok(count) => count.get() as u32,
+        Err(_) => 4,
+    };
+    let thread_count = u32::max(
+        config.get_uint_or_default("chunk_loader_threads", default_thread_count),
+        1,
+    );
+    let output_capacity = u32::max(config.get_uint_or_default("chunk_output_capacity", 64), 1);
+    (thread_count as usize, output_capacity as usize)
+}
+
+fn create_upload_budget(config: &Config) -> UploadBudget {
+    let time_ms = config.get_float_or_default("chunk_upload_budget_ms", 4.);
+    let kilobytes = config.get_uint_or_default("chunk_upload_budget_kb", 0);
+    info!(
+        "Chunk upload budget per frame is {} ms, {} kB (0 = unlimited)",
+        time_ms, kilobytes
+    );
+    UploadBudget::new(time_ms, kilobytes)
+}
+
+fn create_retry_queue(config: &Config) -> RetryQueue {
+    let max_retries = config.get_uint_or_default("chunk_build_retries", 3);
+    let delay_ms = config.get_uint_or_default("chunk_retry_delay_ms", 250);
+    RetryQueue::new(max_retries, Duration::from_millis(delay_ms as u64))
+}
+
+fn create_mesh_settings(config: &Config) -> MeshSettings {
+    let flat_shading = config.get_bool_or_default("terrain_flat_shading", false);
+    let height_format = match config
+        .get_str_or_default("height_map_format", "u16")
+        .as_str()
+    {
+        "f32" => HeightFormat::Float,
+        "u16" => HeightFormat::Quantized,
+        unknown => {
+            warn!("Unknown height map format '{}', using f32", unknown);
+            HeightFormat::Float
+        }
+    };
+    let max_vertical_errors = if config.get_bool_or_default("terrain_adaptive_mesh", true) {
+        Some([
+            config.get_float_or_default("terrain_max_error_lod0", 0.1) as f64,
+            config.get_float_or_default("terrain_max_error_lod1", 0.5) as f64,
+            config.get_float_or_default("terrain_max_error_lod2", 2.) as f64,
+        ])
+    } else {
+        None
+    };
+    info!(
+        "Terrain flat shading = {}, height map format = {:?}, max vertical errors = {:?}",
+        flat_shading, height_format, max_vertical_errors
+    );
+    MeshSettings::new(flat_shading, height_format, max_vertical_errors)
+}
+
+fn create_chunk_cache(config: &Config) -> ChunkCache {
+    let budget_mb = config.get_uint_or_default("chunk_cache_budget", 256);
+    let keep_gpu = config.get_bool_or_default("chunk_cache_gpu", true);
+    info!(
+        "Chunk cache budget is {} MB, keeping gpu buffers = {}",
+        budget_mb, keep_gpu
+    );
+    ChunkCache::new(budget_mb as usize * 1024 * 1024, keep_gpu)
+}
+
 fn load_surface_shader(config: &Config) -> Result<ShaderProgram, WorldError> {
     let surface_shader_dir = config.get_str("surface_shader_dir")?.to_owned();
     let surface_shader_program = ShaderProgramBuilder::new()
@@ -340,3 +992,25 @@ fn load_surface_shader(config: &Config) -> Result<ShaderProgram, WorldError> {
     }
     Ok(surface_shader_program)
 }
+
+fn load_impostor_shader(config: &Config) -> Result<ShaderProgram, WorldError> {
+    let impostor_shader_dir = config.get_str("impostor_shader_dir")?.to_owned();
+    let impostor_shader_program = ShaderProgramBuilder::new()
+        .add_vertex_shader((impostor_shader_dir.clone() + "/VertexShader.glsl").as_str())
+        .add_fragment_shader((impostor_shader_dir + "/FragmentShader.glsl").as_str())
+        .add_resource("atlas")
+        .add_resource("view_projection")
+        .add_resource("view_count")
+        .add_resource("half_size")
+        .add_resource("center_height")
+        .add_resource("view_pos")
+        .add_resource("light_pos")
+        .add_resource("fog_color")
+        .finish()?;
+    // the atlas of the drawn prototype gets bound to slot 0
+    impostor_shader_program.use_program();
+    if let Err(e) = impostor_shader_program.set_resource_integer("atlas", 0) {
+        return Err(GraphicsError::from(e).into());
+    }
+    Ok(impostor_shader_program)
+}